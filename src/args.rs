@@ -1,5 +1,7 @@
 use clap::{App, AppSettings, Arg, SubCommand};
 
+use crate::source::Level;
+
 pub fn clap_app() -> App<'static, 'static> {
     App::new(env!("CARGO_PKG_NAME"))
         .version(env!("CARGO_PKG_VERSION"))
@@ -37,4 +39,56 @@ pub fn clap_app() -> App<'static, 'static> {
                         .takes_value(true),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("send")
+                .about("Send one message and exit")
+                .arg(
+                    Arg::with_name("config")
+                        .long("config")
+                        .short("c")
+                        .help("Path to config file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("title")
+                        .long("title")
+                        .short("t")
+                        .help("Title of the message")
+                        .value_name("TITLE")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("body")
+                        .long("body")
+                        .short("b")
+                        .help("Text of the message, read from stdin if not set")
+                        .value_name("TEXT")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("level")
+                        .long("level")
+                        .short("l")
+                        .help("Level of the message, e.g. info or error")
+                        .value_name("LEVEL")
+                        .takes_value(true)
+                        .validator(|level| {
+                            level
+                                .parse::<Level>()
+                                .map(|_| ())
+                                .map_err(|error| error.to_string())
+                        }),
+                )
+                .arg(
+                    Arg::with_name("attachment")
+                        .long("attachment")
+                        .short("a")
+                        .help("File to attach to the message")
+                        .value_name("FILE")
+                        .takes_value(true),
+                ),
+        )
 }
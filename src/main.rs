@@ -1,6 +1,11 @@
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use clap::ArgMatches;
 use futures::StreamExt;
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+    process,
+};
 
 mod args;
 mod config;
@@ -8,8 +13,14 @@ mod source;
 mod telegram;
 
 use config::Config;
+use source::LogRecord;
 use telegram::{Telegram, TelegramConfig};
 
+/// Exit status of `send` when the message can't be built from the arguments
+const EXIT_INVALID_INPUT: i32 = 2;
+/// Exit status of `send` when Telegram doesn't accept the message
+const EXIT_SEND_FAILED: i32 = 1;
+
 async fn run_echo_id(matches: &ArgMatches<'_>) -> Result<()> {
     let token = matches.value_of("token").map(String::from).unwrap();
     let proxy = matches.value_of("proxy").map(String::from);
@@ -22,6 +33,60 @@ async fn run_echo_id(matches: &ArgMatches<'_>) -> Result<()> {
     Ok(())
 }
 
+fn prepare_send(matches: &ArgMatches<'_>) -> Result<(Config, LogRecord, Option<PathBuf>)> {
+    let config = Config::from_file(matches.value_of("config").unwrap())?;
+
+    let title = matches.value_of("title").unwrap();
+    let body = match matches.value_of("body") {
+        Some(body) => body.to_owned(),
+        None => {
+            let mut body = String::new();
+            io::stdin().read_to_string(&mut body)?;
+            body.trim_end().to_owned()
+        }
+    };
+
+    let mut record = LogRecord::new(title, body);
+    if let Some(level) = matches.value_of("level") {
+        record = record.with_level(level.parse()?);
+    }
+
+    let attachment = matches.value_of("attachment").map(PathBuf::from);
+    if let Some(path) = &attachment {
+        if !path.is_file() {
+            return Err(anyhow!("Attachment {} is not a file", path.display()));
+        }
+    }
+
+    Ok((config, record, attachment))
+}
+
+async fn run_send(matches: &ArgMatches<'_>) -> i32 {
+    let (config, record, attachment) = match prepare_send(matches) {
+        Ok(prepared) => prepared,
+        Err(error) => {
+            eprintln!("{:?}", error);
+            return EXIT_INVALID_INPUT;
+        }
+    };
+
+    let result = async {
+        let telegram = Telegram::new(config.telegram)?;
+        match attachment {
+            Some(path) => telegram.send_file(record, &path).await,
+            None => telegram.send_record(record).await,
+        }
+    };
+
+    match result.await {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("{:?}", error);
+            EXIT_SEND_FAILED
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let matches = args::clap_app().get_matches();
@@ -30,6 +95,9 @@ async fn main() -> Result<()> {
 
         return Ok(());
     }
+    if let Some(send_args) = matches.subcommand_matches("send") {
+        process::exit(run_send(send_args).await);
+    }
 
     let config_path = matches.value_of("config").unwrap();
     let config = Config::from_file(config_path)?;
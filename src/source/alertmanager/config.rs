@@ -0,0 +1,22 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AlertmanagerLogSourceConfig {
+    pub addr: SocketAddr,
+    pub path: String,
+    pub token: Option<String>,
+    pub max_body: usize,
+}
+
+impl Default for AlertmanagerLogSourceConfig {
+    fn default() -> Self {
+        AlertmanagerLogSourceConfig {
+            addr: SocketAddr::from(([127, 0, 0, 1], 9095)),
+            path: String::from("/alertmanager"),
+            token: None,
+            max_body: 1024 * 1024,
+        }
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::Result;
+use hyper::{
+    server::{conn::AddrIncoming, Builder},
+    Body, Request, Server,
+};
+use serde::Deserialize;
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::source::{
+    webhook::{self, Rejection},
+    Level, LogRecord, LogSource, LogSourceStream,
+};
+
+mod config;
+pub use self::config::AlertmanagerLogSourceConfig;
+
+type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Notification {
+    group_key: String,
+    status: String,
+    #[serde(default)]
+    group_labels: Labels,
+    #[serde(default)]
+    common_labels: Labels,
+    #[serde(default)]
+    alerts: Vec<Alert>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Alert {
+    status: String,
+    #[serde(default)]
+    labels: Labels,
+    #[serde(default)]
+    annotations: Labels,
+}
+
+pub struct AlertmanagerLogSource {
+    builder: Builder<AddrIncoming>,
+    config: Arc<AlertmanagerLogSourceConfig>,
+}
+
+impl AlertmanagerLogSource {
+    pub fn new(config: AlertmanagerLogSourceConfig) -> Result<Self> {
+        let builder = Server::try_bind(&config.addr)?;
+
+        Ok(AlertmanagerLogSource {
+            builder,
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl LogSource for AlertmanagerLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let config = self.config;
+
+        webhook::serve(self.builder, move |req| parse_request(config.clone(), req))
+    }
+}
+
+async fn parse_request(
+    config: Arc<AlertmanagerLogSourceConfig>,
+    req: Request<Body>,
+) -> Result<Vec<LogRecord>, Rejection> {
+    if req.uri().path() != config.path {
+        return Err(webhook::not_found());
+    }
+    webhook::check_request(&req, config.token.as_deref())?;
+
+    let bytes = webhook::read_body(req, config.max_body).await?;
+    let notification: Notification =
+        serde_json::from_slice(&bytes).map_err(webhook::bad_request)?;
+
+    Ok(vec![notification_to_record(notification)])
+}
+
+fn notification_to_record(notification: Notification) -> LogRecord {
+    let resolved = notification.status == "resolved";
+    let firing = notification
+        .alerts
+        .iter()
+        .filter(|alert| alert.status == "firing")
+        .count();
+
+    let group_labels = format_labels(&notification.group_labels);
+    let title = if resolved {
+        format!("Resolved: {}", group_labels)
+    } else {
+        format!("Firing {}: {}", firing, group_labels)
+    };
+
+    let body = notification
+        .alerts
+        .iter()
+        .map(|alert| format_alert(alert, &notification.group_labels))
+        .collect::<Vec<String>>()
+        .join("\n\n");
+
+    let level = if resolved {
+        Level::Info
+    } else {
+        notification
+            .common_labels
+            .get("severity")
+            .and_then(|severity| severity.parse().ok())
+            .unwrap_or(Level::Error)
+    };
+
+    LogRecord::new(title, body)
+        .with_level(level)
+        .with_metadata("status", notification.status)
+        .with_thread(notification.group_key, resolved)
+}
+
+fn format_labels(labels: &Labels) -> String {
+    labels
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn format_alert(alert: &Alert, group_labels: &Labels) -> String {
+    let labels: Labels = alert
+        .labels
+        .iter()
+        .filter(|(key, _)| !group_labels.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+
+    let mut lines = vec![format!("[{}] {}", alert.status, format_labels(&labels))];
+    for (key, value) in &alert.annotations {
+        lines.push(format!("{}: {}", key, value));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use reqwest::{Client, StatusCode};
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{AlertmanagerLogSource, AlertmanagerLogSourceConfig};
+
+    static FIRING: &str = r#"{
+        "version": "4",
+        "groupKey": "{}:{alertname=\"DiskFull\"}",
+        "status": "firing",
+        "receiver": "logram",
+        "groupLabels": {"alertname": "DiskFull"},
+        "commonLabels": {"alertname": "DiskFull", "severity": "warning"},
+        "commonAnnotations": {},
+        "externalURL": "http://alertmanager:9093",
+        "alerts": [
+            {
+                "status": "firing",
+                "labels": {"alertname": "DiskFull", "instance": "db1", "severity": "warning"},
+                "annotations": {"summary": "Disk is 95% full"},
+                "startsAt": "2021-01-01T00:00:00Z",
+                "endsAt": "0001-01-01T00:00:00Z",
+                "fingerprint": "a1"
+            }
+        ]
+    }"#;
+
+    static RESOLVED: &str = r#"{
+        "version": "4",
+        "groupKey": "{}:{alertname=\"DiskFull\"}",
+        "status": "resolved",
+        "groupLabels": {"alertname": "DiskFull"},
+        "commonLabels": {"alertname": "DiskFull", "severity": "warning"},
+        "alerts": [
+            {
+                "status": "resolved",
+                "labels": {"alertname": "DiskFull", "instance": "db1", "severity": "warning"},
+                "annotations": {"summary": "Disk is 95% full"}
+            }
+        ]
+    }"#;
+
+    #[tokio::test]
+    async fn main() {
+        let config = AlertmanagerLogSourceConfig {
+            addr: "127.0.0.1:19095".parse().unwrap(),
+            path: String::from("/alertmanager"),
+            token: None,
+            max_body: 1024 * 1024,
+        };
+
+        let source = AlertmanagerLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+        let client = Client::new();
+
+        for payload in &[FIRING, RESOLVED] {
+            let response = client
+                .post("http://127.0.0.1:19095/alertmanager")
+                .header("Content-Type", "application/json")
+                .body(*payload)
+                .send()
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+        }
+
+        let group_key = "{}:{alertname=\"DiskFull\"}";
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new(
+                "Firing 1: alertname=DiskFull",
+                "[firing] instance=db1, severity=warning\nsummary: Disk is 95% full",
+            )
+            .with_level(Level::Warning)
+            .with_metadata("status", "firing")
+            .with_thread(group_key, false),
+            LogRecord::new(
+                "Resolved: alertname=DiskFull",
+                "[resolved] instance=db1, severity=warning\nsummary: Disk is 95% full",
+            )
+            .with_level(Level::Info)
+            .with_metadata("status", "resolved")
+            .with_thread(group_key, true),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::source::OverflowPolicy;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Input {
+    File,
+    Socket,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AuditLogSourceConfig {
+    pub input: Input,
+    pub path: PathBuf,
+    pub socket: PathBuf,
+    pub types: Vec<String>,
+    pub exclude_types: Vec<String>,
+    pub keys: Vec<String>,
+    pub timeout: u64,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for AuditLogSourceConfig {
+    fn default() -> Self {
+        AuditLogSourceConfig {
+            input: Input::File,
+            path: PathBuf::from("/var/log/audit/audit.log"),
+            socket: PathBuf::from("/var/run/audispd_events"),
+            types: Vec::new(),
+            exclude_types: Vec::new(),
+            keys: Vec::new(),
+            timeout: 500,
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+}
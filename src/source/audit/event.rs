@@ -0,0 +1,278 @@
+use std::time::{Duration, Instant};
+
+use super::line::AuditLine;
+use crate::source::{Level, LogRecord};
+
+/// Records which only add details to the main record of an event
+const AUXILIARY_TYPES: &[&str] = &[
+    "SYSCALL",
+    "EOE",
+    "PROCTITLE",
+    "CWD",
+    "PATH",
+    "SOCKADDR",
+    "BPRM_FCAPS",
+    "MMAP",
+];
+
+/// All records of one audit event
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    pub lines: Vec<AuditLine>,
+}
+
+impl AuditEvent {
+    fn first(&self) -> &AuditLine {
+        &self.lines[0]
+    }
+    fn field(&self, name: &str) -> Option<&str> {
+        self.lines.iter().find_map(|line| line.field(name))
+    }
+    pub fn has_type(&self, kinds: &[String]) -> bool {
+        self.lines.iter().any(|line| kinds.contains(&line.kind))
+    }
+    /// Audit keys (`-k` of the rules) which matched the event
+    pub fn keys(&self) -> Vec<&str> {
+        match self.field("key") {
+            Some("(null)") | None => Vec::new(),
+            Some(keys) => keys.split(',').collect(),
+        }
+    }
+    /// Type of the record the event is about, e.g. EXECVE rather than SYSCALL
+    pub fn main_type(&self) -> &str {
+        self.lines
+            .iter()
+            .map(|line| line.kind.as_str())
+            .find(|kind| !AUXILIARY_TYPES.contains(kind))
+            .unwrap_or_else(|| self.first().kind.as_str())
+    }
+    fn level(&self) -> Level {
+        let failed = self.lines.iter().any(|line| {
+            line.kind.starts_with("ANOM_")
+                || (line.kind == "AVC" && line.text.contains("denied"))
+                || matches!(line.field("res"), Some("failed") | Some("fail") | Some("0"))
+                || line.field("success") == Some("no")
+        });
+
+        if failed {
+            Level::Warning
+        } else {
+            Level::Info
+        }
+    }
+    pub fn into_record(self) -> LogRecord {
+        let keys = self.keys().join(",");
+        let main_type = self.main_type().to_owned();
+        let title = if keys.is_empty() {
+            format!("Audit: {}", main_type)
+        } else {
+            format!("Audit {}: {}", keys, main_type)
+        };
+
+        let body = self
+            .lines
+            .iter()
+            .filter(|line| line.kind != "EOE")
+            .map(format_line)
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        let first = self.first();
+        let mut record = LogRecord::new(title, body)
+            .with_level(self.level())
+            .with_timestamp(first.timestamp)
+            .with_metadata("id", first.id.clone())
+            .with_metadata("type", main_type);
+        if !keys.is_empty() {
+            record = record.with_metadata("key", keys);
+        }
+        if let Some(node) = &first.node {
+            record = record.with_metadata("node", node.clone());
+        }
+        for name in &["auid", "uid", "pid", "exe", "comm", "res", "success"] {
+            if let Some(value) = self.field(name) {
+                record = record.with_metadata(*name, value);
+            }
+        }
+
+        record
+    }
+}
+
+fn format_line(line: &AuditLine) -> String {
+    let details = match line.kind.as_str() {
+        "EXECVE" => line
+            .fields
+            .iter()
+            .filter(|(key, _)| key != "argc")
+            .map(|(_, value)| value.clone())
+            .collect::<Vec<String>>()
+            .join(" "),
+        "PROCTITLE" => line.field("proctitle").unwrap_or_default().to_owned(),
+        kind => {
+            let fields = line
+                .fields
+                .iter()
+                .map(|(key, value)| {
+                    if value.contains(char::is_whitespace) || value.is_empty() {
+                        format!("{}=\"{}\"", key, value)
+                    } else {
+                        format!("{}={}", key, value)
+                    }
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+
+            // keep "avc: denied { read }", which isn't a key=value pair
+            match line.text.find(" for ") {
+                Some(end) if kind == "AVC" => {
+                    let summary = line.text[..end].split_whitespace().collect::<Vec<&str>>();
+                    format!("{} {}", summary.join(" "), fields)
+                }
+                _ => fields,
+            }
+        }
+    };
+
+    format!("{}: {}", line.kind, details)
+}
+
+struct Pending {
+    event: AuditEvent,
+    started: Instant,
+}
+
+/// Groups the lines into events by their ID. An event is complete on its EOE
+/// record, the events without EOE (single record ones) are complete after the timeout
+pub struct Correlator {
+    timeout: Duration,
+    pending: Vec<Pending>,
+}
+
+impl Correlator {
+    pub fn new(timeout: Duration) -> Self {
+        Correlator {
+            timeout,
+            pending: Vec::new(),
+        }
+    }
+    pub fn push(&mut self, line: AuditLine, now: Instant) -> Option<AuditEvent> {
+        let position = self.pending.iter().position(|pending| {
+            pending.event.first().id == line.id && pending.event.first().node == line.node
+        });
+        let is_end = line.kind == "EOE";
+
+        match position {
+            Some(position) if is_end => Some(self.pending.remove(position).event),
+            Some(position) => {
+                self.pending[position].event.lines.push(line);
+                None
+            }
+            // EOE of an event which was already sent on the timeout
+            None if is_end => None,
+            None => {
+                self.pending.push(Pending {
+                    event: AuditEvent { lines: vec![line] },
+                    started: now,
+                });
+                None
+            }
+        }
+    }
+    pub fn expired(&mut self, now: Instant) -> Vec<AuditEvent> {
+        let timeout = self.timeout;
+        let (expired, pending) = self
+            .pending
+            .drain(..)
+            .partition(|pending| now.duration_since(pending.started) >= timeout);
+        self.pending = pending;
+
+        expired
+            .into_iter()
+            .map(|pending: Pending| pending.event)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use std::time::{Duration, Instant};
+
+    use super::{super::line::AuditLine, Correlator};
+    use crate::source::{Level, LogRecord};
+
+    fn line(text: &str) -> AuditLine {
+        AuditLine::parse(text).unwrap()
+    }
+
+    #[test]
+    fn main() {
+        let mut correlator = Correlator::new(Duration::from_millis(500));
+        let now = Instant::now();
+
+        let lines = [
+            "type=SYSCALL msg=audit(1609556645.123:42): syscall=59 success=yes exit=0 auid=1000 uid=0 pid=9 comm=\"cat\" exe=\"/usr/bin/cat\" key=\"shadow\"",
+            "type=USER_LOGIN msg=audit(1609556645.200:43): pid=7 uid=0 msg='op=login acct=\"root\" addr=203.0.113.7 res=failed'",
+            "type=EXECVE msg=audit(1609556645.123:42): argc=2 a0=\"cat\" a1=\"/etc/shadow\"",
+            "type=PROCTITLE msg=audit(1609556645.123:42): proctitle=636174002F6574632F736861646F77",
+        ];
+        for text in &lines {
+            assert_eq!(correlator.push(line(text), now), None);
+        }
+
+        let eoe = line("type=EOE msg=audit(1609556645.123:42): ");
+        let event = correlator.push(eoe, now).unwrap();
+        assert_eq!(event.main_type(), "EXECVE");
+
+        let expected = LogRecord::new(
+            "Audit shadow: EXECVE",
+            "SYSCALL: syscall=59 success=yes exit=0 auid=1000 uid=0 pid=9 comm=cat exe=/usr/bin/cat key=shadow\n\
+             EXECVE: cat /etc/shadow\n\
+             PROCTITLE: cat /etc/shadow",
+        )
+        .with_level(Level::Info)
+        .with_timestamp(Utc.timestamp_millis(1_609_556_645_123))
+        .with_metadata("id", "1609556645.123:42")
+        .with_metadata("type", "EXECVE")
+        .with_metadata("key", "shadow")
+        .with_metadata("auid", "1000")
+        .with_metadata("uid", "0")
+        .with_metadata("pid", "9")
+        .with_metadata("exe", "/usr/bin/cat")
+        .with_metadata("comm", "cat")
+        .with_metadata("success", "yes");
+        assert_eq!(event.into_record(), expected);
+
+        assert!(correlator
+            .expired(now + Duration::from_millis(100))
+            .is_empty());
+        let events = correlator.expired(now + Duration::from_millis(500));
+        assert_eq!(events.len(), 1);
+
+        let expected = LogRecord::new(
+            "Audit: USER_LOGIN",
+            "USER_LOGIN: pid=7 uid=0 op=login acct=root addr=203.0.113.7 res=failed",
+        )
+        .with_level(Level::Warning)
+        .with_timestamp(Utc.timestamp_millis(1_609_556_645_200))
+        .with_metadata("id", "1609556645.200:43")
+        .with_metadata("type", "USER_LOGIN")
+        .with_metadata("uid", "0")
+        .with_metadata("pid", "7")
+        .with_metadata("res", "failed");
+        assert_eq!(events[0].clone().into_record(), expected);
+
+        let avc = line("type=AVC msg=audit(1609556646.000:44): avc:  denied  { read } for  pid=9 comm=\"cat\" tclass=file");
+        correlator.push(avc, now);
+        let record = correlator
+            .expired(now + Duration::from_secs(1))
+            .remove(0)
+            .into_record();
+        assert_eq!(
+            record.body,
+            "AVC: avc: denied { read } pid=9 comm=cat tclass=file"
+        );
+        assert_eq!(record.level, Some(Level::Warning));
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::{anyhow, Result};
+use std::{
+    io::{BufRead, BufReader, ErrorKind},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    thread,
+    time::Duration,
+};
+
+use crate::source::tail::FileTail;
+
+/// Reads the events of the audisp `af_unix` plugin in the string format
+pub struct SocketReader {
+    path: PathBuf,
+    reader: Option<BufReader<UnixStream>>,
+    partial: Vec<u8>,
+    /// Why the connection was lost, returned after the lines read before
+    lost: Option<anyhow::Error>,
+    reconnected: bool,
+}
+
+impl SocketReader {
+    pub fn new(path: PathBuf) -> Result<Self> {
+        let mut socket = SocketReader {
+            path,
+            reader: None,
+            partial: Vec::new(),
+            lost: None,
+            reconnected: false,
+        };
+        socket.connect()?;
+
+        Ok(socket)
+    }
+    fn connect(&mut self) -> Result<()> {
+        let stream = UnixStream::connect(&self.path)?;
+        self.reader = Some(BufReader::new(stream));
+        self.partial.clear();
+
+        Ok(())
+    }
+    /// Reads the available lines, the loss of the connection is returned as an error
+    /// once, then the socket is reconnected silently
+    fn read_lines(&mut self, timeout: Duration) -> Result<Vec<String>> {
+        if let Some(error) = self.lost.take() {
+            return Err(error);
+        }
+
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            // audispd is restarted with auditd, reconnect to the new socket
+            None => {
+                thread::sleep(timeout);
+                if self.connect().is_err() {
+                    return Ok(Vec::new());
+                }
+                self.reconnected = true;
+                self.reader.as_mut().unwrap()
+            }
+        };
+        reader.get_ref().set_read_timeout(Some(timeout))?;
+
+        let mut lines = Vec::new();
+        loop {
+            match reader.read_until(b'\n', &mut self.partial) {
+                Ok(0) => {
+                    self.reader = None;
+                    self.lost = Some(anyhow!("{} is closed by audispd", self.path.display()));
+                    break;
+                }
+                Ok(_) if self.partial.ends_with(b"\n") => {
+                    lines.push(String::from_utf8_lossy(&self.partial).trim_end().to_owned());
+                    self.partial.clear();
+
+                    if reader.buffer().is_empty() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(error)
+                    if error.kind() == ErrorKind::WouldBlock
+                        || error.kind() == ErrorKind::TimedOut =>
+                {
+                    break
+                }
+                Err(error) => {
+                    self.reader = None;
+                    self.lost = Some(anyhow!("Reading {}: {}", self.path.display(), error));
+                    break;
+                }
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+pub enum LineInput {
+    File(FileTail),
+    Socket(SocketReader),
+}
+
+impl LineInput {
+    /// Waits up to `timeout` for new lines
+    pub fn read(&mut self, timeout: Duration) -> Result<Vec<String>> {
+        match self {
+            LineInput::File(tail) => tail.wait_lines(timeout),
+            LineInput::Socket(socket) => socket.read_lines(timeout),
+        }
+    }
+    /// Returns `true` once after the lost socket is connected again
+    pub fn take_reconnected(&mut self) -> bool {
+        match self {
+            LineInput::File(_) => false,
+            LineInput::Socket(socket) => std::mem::take(&mut socket.reconnected),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, io::Write, os::unix::net::UnixListener, thread, time::Duration};
+
+    use super::{LineInput, SocketReader};
+
+    #[test]
+    fn main() {
+        let path = env::temp_dir().join("logram_test_audispd_events");
+        let _ = fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let writer = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"first\nsec").unwrap();
+            thread::sleep(Duration::from_millis(300));
+            stream.write_all(b"ond\n").unwrap();
+        });
+
+        let mut socket = SocketReader::new(path.clone()).unwrap();
+        let timeout = Duration::from_millis(100);
+        let mut lines = Vec::new();
+        while lines.len() < 2 {
+            lines.extend(socket.read_lines(timeout).unwrap());
+        }
+        writer.join().unwrap();
+
+        assert_eq!(lines, vec!["first", "second"]);
+        // the lost connection is reported once
+        let mut input = LineInput::Socket(socket);
+        let results: Vec<_> = (0..3).map(|_| input.read(timeout)).collect();
+        assert_eq!(results.iter().filter(|result| result.is_err()).count(), 1);
+        assert!(!input.take_reconnected());
+
+        // audispd is restarted
+        fs::remove_file(&path).unwrap();
+        let listener = UnixListener::bind(&path).unwrap();
+        let writer = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.write_all(b"third\n").unwrap();
+        });
+
+        let mut lines = Vec::new();
+        while lines.is_empty() {
+            lines.extend(input.read(timeout).unwrap());
+        }
+        writer.join().unwrap();
+        assert_eq!(lines, vec!["third"]);
+        assert!(input.take_reconnected());
+        assert!(!input.take_reconnected());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Fields which auditd hex-encodes when their value contains spaces, quotes or
+/// control characters, along with the `aN` arguments of EXECVE
+const ENCODED_FIELDS: &[&str] = &[
+    "proctitle",
+    "cmd",
+    "comm",
+    "exe",
+    "name",
+    "cwd",
+    "key",
+    "acct",
+    "path",
+    "data",
+];
+
+/// One line of the audit log, e.g.
+/// `type=SYSCALL msg=audit(1609556645.123:42): arch=c000003e syscall=59 ...`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditLine {
+    pub node: Option<String>,
+    pub kind: String,
+    /// `timestamp:serial` part of `msg=audit(...)`, the same for all records of an event
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    /// Text after the event ID, as it was logged
+    pub text: String,
+    pub fields: Vec<(String, String)>,
+}
+
+impl AuditLine {
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut rest = line.trim();
+
+        let mut node = None;
+        if let Some(after) = rest.strip_prefix("node=") {
+            let end = after.find(' ')?;
+            node = Some(after[..end].to_owned());
+            rest = after[end..].trim_start();
+        }
+
+        let after = rest.strip_prefix("type=")?;
+        let end = after.find(' ')?;
+        let kind = after[..end].to_owned();
+
+        let after = after[end..].trim_start().strip_prefix("msg=audit(")?;
+        let end = after.find(')')?;
+        let id = after[..end].to_owned();
+        let text = after[end + 1..]
+            .trim_start_matches(':')
+            .trim()
+            .replace('\x1d', " ");
+
+        let (seconds, millis) = id.split(':').next()?.split_once('.')?;
+        let timestamp = Utc
+            .timestamp_opt(
+                seconds.parse().ok()?,
+                millis.parse::<u32>().ok()? * 1_000_000,
+            )
+            .single()?;
+
+        let fields = parse_fields(&text)
+            .into_iter()
+            .map(|(key, value, quoted)| {
+                let value = if quoted || !is_encoded(&kind, &key) {
+                    value
+                } else {
+                    decode_value(&key, value)
+                };
+                (key, value)
+            })
+            .collect();
+
+        Some(AuditLine {
+            node,
+            kind,
+            id,
+            timestamp,
+            text,
+            fields,
+        })
+    }
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn is_encoded(kind: &str, key: &str) -> bool {
+    let is_argument = kind == "EXECVE"
+        && key.len() > 1
+        && key.starts_with('a')
+        && key[1..].bytes().all(|byte| byte.is_ascii_digit());
+
+    is_argument || ENCODED_FIELDS.contains(&key)
+}
+
+/// Splits `key=value key="quoted value" msg='nested=fields'` into the fields,
+/// the nested fields of single quoted values are returned in place of them
+fn parse_fields(text: &str) -> Vec<(String, String, bool)> {
+    let mut fields = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        rest = &rest[start..];
+
+        let token_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let eq = match rest[..token_end].find('=') {
+            Some(eq) => eq,
+            // words without a value, e.g. "denied" or "{ read }" of AVC
+            None => {
+                rest = &rest[token_end..];
+                continue;
+            }
+        };
+
+        let key = rest[..eq].to_owned();
+        let value = &rest[eq + 1..];
+        match value.chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => {
+                let end = value[1..].find(quote).map_or(value.len(), |end| end + 1);
+                let inner = &value[1..end];
+                if quote == '\'' {
+                    fields.extend(parse_fields(inner));
+                } else {
+                    fields.push((key, inner.to_owned(), true));
+                }
+                rest = &value[(end + 1).min(value.len())..];
+            }
+            _ => {
+                let end = value.find(char::is_whitespace).unwrap_or(value.len());
+                fields.push((key, value[..end].to_owned(), false));
+                rest = &value[end..];
+            }
+        }
+    }
+
+    fields
+}
+
+fn decode_value(key: &str, value: String) -> String {
+    if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return value;
+    }
+
+    let bytes: Option<Vec<u8>> = value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| match pair {
+            [high, low] => {
+                u8::from_str_radix(&format!("{}{}", *high as char, *low as char), 16).ok()
+            }
+            _ => None,
+        })
+        .collect();
+    let bytes = match bytes {
+        Some(bytes) => bytes,
+        None => return value,
+    };
+    let separator = if key == "key" { "," } else { " " };
+
+    String::from_utf8_lossy(&bytes)
+        .split(&['\0', '\x01'][..])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::AuditLine;
+
+    fn fields(line: &AuditLine) -> Vec<(&str, &str)> {
+        line.fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn main() {
+        let line = AuditLine::parse(
+            r#"type=EXECVE msg=audit(1609556645.123:42): argc=3 a0="cat" a1="-n" a2=2F746D702F6D792066696C65"#,
+        )
+        .unwrap();
+        assert_eq!(line.node, None);
+        assert_eq!(line.kind, "EXECVE");
+        assert_eq!(line.id, "1609556645.123:42");
+        assert_eq!(line.timestamp, Utc.timestamp_millis(1_609_556_645_123));
+        assert_eq!(
+            fields(&line),
+            vec![
+                ("argc", "3"),
+                ("a0", "cat"),
+                ("a1", "-n"),
+                ("a2", "/tmp/my file")
+            ]
+        );
+
+        let line = AuditLine::parse(
+            "node=web1 type=USER_LOGIN msg=audit(1609556645.500:43): pid=7 uid=0 auid=4294967295 \
+             msg='op=login acct=\"root\" exe=\"/usr/sbin/sshd\" addr=203.0.113.7 res=failed'",
+        )
+        .unwrap();
+        assert_eq!(line.node.as_deref(), Some("web1"));
+        assert_eq!(line.field("acct"), Some("root"));
+        assert_eq!(line.field("res"), Some("failed"));
+        assert_eq!(line.field("auid"), Some("4294967295"));
+
+        // SYSCALL arguments are numbers, not encoded strings
+        let line = AuditLine::parse(
+            "type=SYSCALL msg=audit(1609556645.123:42): syscall=59 a0=55d2 comm=\"cat\" \
+             key=70617373776401696465\x1dAUID=\"alice\"",
+        )
+        .unwrap();
+        assert_eq!(line.field("a0"), Some("55d2"));
+        assert_eq!(line.field("key"), Some("passwd,ide"));
+        assert_eq!(line.field("AUID"), Some("alice"));
+
+        let line = AuditLine::parse(
+            "type=AVC msg=audit(1609556645.009:44): avc:  denied  { read } for  pid=9 comm=\"cat\" tclass=file",
+        )
+        .unwrap();
+        assert_eq!(line.timestamp, Utc.timestamp_millis(1_609_556_645_009));
+        assert_eq!(
+            fields(&line),
+            vec![("pid", "9"), ("comm", "cat"), ("tclass", "file")]
+        );
+
+        assert!(AuditLine::parse("random line").is_none());
+    }
+}
@@ -0,0 +1,199 @@
+use anyhow::Result;
+use futures::{
+    channel::oneshot::{self, Sender as OneshotSender},
+    executor,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::source::{
+    tail::FileTail, BlockingReceiver, BlockingSender, LogRecord, LogSource, LogSourceStream,
+};
+
+mod config;
+mod event;
+mod input;
+mod line;
+pub use self::config::AuditLogSourceConfig;
+use self::{
+    config::Input,
+    event::{AuditEvent, Correlator},
+    input::{LineInput, SocketReader},
+    line::AuditLine,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct AuditLogSource {
+    receiver: BlockingReceiver,
+}
+
+impl AuditLogSource {
+    pub fn new(config: AuditLogSourceConfig) -> Result<Self> {
+        let (init_tx, init_rx) = oneshot::channel();
+        let (record_tx, record_rx) = BlockingSender::channel(config.buffer, config.overflow);
+
+        thread::spawn(move || run_inner(config, init_tx, record_tx));
+
+        match executor::block_on(init_rx).unwrap() {
+            Err(error) => Err(error),
+            Ok(_) => Ok(AuditLogSource {
+                receiver: record_rx,
+            }),
+        }
+    }
+}
+
+impl LogSource for AuditLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        Box::pin(self.receiver)
+    }
+}
+
+struct AuditLogSourceInner {
+    input: LineInput,
+    correlator: Correlator,
+    types: Vec<String>,
+    exclude_types: Vec<String>,
+    keys: Vec<String>,
+}
+
+impl AuditLogSourceInner {
+    fn new(config: AuditLogSourceConfig) -> Result<Self> {
+        let input = match config.input {
+            Input::File => LineInput::File(FileTail::new(config.path, true)?),
+            Input::Socket => LineInput::Socket(SocketReader::new(config.socket)?),
+        };
+
+        Ok(AuditLogSourceInner {
+            input,
+            correlator: Correlator::new(Duration::from_millis(config.timeout)),
+            types: config.types,
+            exclude_types: config.exclude_types,
+            keys: config.keys,
+        })
+    }
+    fn next_records(&mut self) -> Result<Vec<LogRecord>> {
+        let lines = self.input.read(POLL_INTERVAL)?;
+        let now = Instant::now();
+
+        let mut events = Vec::new();
+        for line in lines.iter().filter_map(|line| AuditLine::parse(line)) {
+            events.extend(self.correlator.push(line, now));
+        }
+        events.extend(self.correlator.expired(now));
+
+        let mut records = Vec::new();
+        if self.input.take_reconnected() {
+            records.push(LogRecord::only_title(String::from(
+                "Reconnected to the audispd socket",
+            )));
+        }
+        records.extend(
+            events
+                .into_iter()
+                .filter(|event| self.is_wanted(event))
+                .map(AuditEvent::into_record),
+        );
+
+        Ok(records)
+    }
+    fn is_wanted(&self, event: &AuditEvent) -> bool {
+        let keys = event.keys();
+
+        (self.types.is_empty() || event.has_type(&self.types))
+            && !event.has_type(&self.exclude_types)
+            && (self.keys.is_empty() || self.keys.iter().any(|key| keys.contains(&key.as_str())))
+    }
+}
+
+fn run_inner(
+    config: AuditLogSourceConfig,
+    init_tx: OneshotSender<Result<()>>,
+    mut record_tx: BlockingSender,
+) {
+    let mut inner = match AuditLogSourceInner::new(config) {
+        Ok(inner) => {
+            init_tx.send(Ok(())).unwrap();
+            inner
+        }
+        Err(error) => {
+            init_tx.send(Err(error)).unwrap();
+            return;
+        }
+    };
+
+    loop {
+        let results = match inner.next_records() {
+            Ok(records) => records.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        };
+
+        for result in results {
+            if record_tx.send(result).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+    };
+
+    use crate::source::{LogRecord, LogSource};
+
+    use super::{AuditLogSource, AuditLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let path = env::temp_dir().join("logram_test_audit.log");
+        fs::write(&path, "").unwrap();
+
+        let config = AuditLogSourceConfig {
+            path: path.clone(),
+            exclude_types: vec![String::from("CRED_REFR")],
+            keys: vec![String::from("identity"), String::from("exec")],
+            timeout: 100,
+            ..Default::default()
+        };
+
+        let source = AuditLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+
+        let lines = [
+            // excluded by the key
+            "type=SYSCALL msg=audit(1609556645.100:41): syscall=2 success=yes key=\"other\"",
+            "type=EOE msg=audit(1609556645.100:41): ",
+            "type=SYSCALL msg=audit(1609556645.123:42): syscall=59 success=yes comm=\"id\" key=\"exec\"",
+            "type=EXECVE msg=audit(1609556645.123:42): argc=1 a0=\"id\"",
+            "type=EOE msg=audit(1609556645.123:42): ",
+            // excluded by the type
+            "type=CRED_REFR msg=audit(1609556645.200:43): pid=7 key=\"identity\"",
+            "type=SYSCALL msg=audit(1609556645.300:44): syscall=82 success=no comm=\"mv\" key=\"identity\"",
+            "type=PATH msg=audit(1609556645.300:44): item=0 name=\"/etc/passwd\"",
+        ];
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        for line in &lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        let titles: Vec<&str> = actual.iter().map(|record| record.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Audit exec: EXECVE", "Audit identity: SYSCALL"]
+        );
+        assert_eq!(
+            actual[1].body,
+            "SYSCALL: syscall=82 success=no comm=mv key=identity\nPATH: item=0 name=/etc/passwd"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+#[derive(Debug)]
+struct Burst {
+    started: Instant,
+    count: usize,
+    users: BTreeSet<String>,
+    methods: BTreeSet<String>,
+    /// Counted "Invalid user" attempts per user, whose "Failed ... for invalid user" line
+    /// (if sshd logs one) is the same attempt
+    invalid: HashMap<String, usize>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BurstSummary {
+    pub ip: String,
+    pub count: usize,
+    pub users: Vec<String>,
+    pub methods: Vec<String>,
+}
+
+/// Counts failed logins per IP within a window which starts at the first failure
+#[derive(Debug)]
+pub struct BurstTracker {
+    window: Duration,
+    threshold: usize,
+    bursts: HashMap<String, Burst>,
+}
+
+impl BurstTracker {
+    pub fn new(window: Duration, threshold: usize) -> Self {
+        BurstTracker {
+            window,
+            threshold,
+            bursts: HashMap::new(),
+        }
+    }
+    pub fn failed(&mut self, ip: &str, user: &str, method: &str, invalid: bool, now: Instant) {
+        let burst = self.burst(ip, user, now);
+        burst.methods.insert(method.to_owned());
+
+        if invalid {
+            if let Some(count) = burst.invalid.get_mut(user).filter(|count| **count > 0) {
+                *count -= 1;
+                return;
+            }
+        }
+        burst.count += 1;
+    }
+    /// Counts the attempt to log in as a nonexistent user, which may have no "Failed" line
+    pub fn invalid_user(&mut self, ip: &str, user: &str, now: Instant) {
+        let burst = self.burst(ip, user, now);
+
+        burst.count += 1;
+        *burst.invalid.entry(user.to_owned()).or_insert(0) += 1;
+    }
+    fn burst(&mut self, ip: &str, user: &str, now: Instant) -> &mut Burst {
+        let burst = self.bursts.entry(ip.to_owned()).or_insert_with(|| Burst {
+            started: now,
+            count: 0,
+            users: BTreeSet::new(),
+            methods: BTreeSet::new(),
+            invalid: HashMap::new(),
+        });
+        burst.users.insert(user.to_owned());
+
+        burst
+    }
+    /// Removes the bursts with elapsed window, returns the ones which reached the threshold
+    pub fn expired(&mut self, now: Instant) -> Vec<BurstSummary> {
+        let window = self.window;
+        let expired: Vec<String> = self
+            .bursts
+            .iter()
+            .filter(|(_, burst)| now.duration_since(burst.started) >= window)
+            .map(|(ip, _)| ip.clone())
+            .collect();
+
+        let mut summaries: Vec<BurstSummary> = expired
+            .into_iter()
+            .filter_map(|ip| {
+                let burst = self.bursts.remove(&ip)?;
+                if burst.count < self.threshold {
+                    return None;
+                }
+
+                Some(BurstSummary {
+                    ip,
+                    count: burst.count,
+                    users: burst.users.into_iter().collect(),
+                    methods: burst.methods.into_iter().collect(),
+                })
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.ip.cmp(&b.ip));
+
+        summaries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{BurstSummary, BurstTracker};
+
+    #[test]
+    fn main() {
+        let mut tracker = BurstTracker::new(Duration::from_secs(300), 3);
+        let now = Instant::now();
+
+        tracker.failed("1.2.3.4", "root", "password", false, now);
+        tracker.invalid_user("1.2.3.4", "oracle", now);
+        tracker.failed("1.2.3.4", "oracle", "password", true, now);
+        tracker.failed("5.6.7.8", "root", "password", false, now);
+        tracker.failed("1.2.3.4", "root", "keyboard-interactive/pam", false, now);
+        assert_eq!(tracker.expired(now + Duration::from_secs(299)), vec![]);
+
+        tracker.failed("5.6.7.8", "root", "password", false, now);
+        for user in &["admin", "test", "admin"] {
+            tracker.invalid_user("9.9.9.9", user, now);
+        }
+        tracker.failed("9.9.9.9", "admin", "password", true, now);
+        tracker.failed("9.9.9.9", "admin", "password", true, now);
+        tracker.failed("9.9.9.9", "admin", "password", true, now);
+
+        let expected = vec![
+            BurstSummary {
+                ip: String::from("1.2.3.4"),
+                count: 3,
+                users: vec![String::from("oracle"), String::from("root")],
+                methods: vec![
+                    String::from("keyboard-interactive/pam"),
+                    String::from("password"),
+                ],
+            },
+            BurstSummary {
+                ip: String::from("9.9.9.9"),
+                count: 4,
+                users: vec![String::from("admin"), String::from("test")],
+                methods: vec![String::from("password")],
+            },
+        ];
+        assert_eq!(tracker.expired(now + Duration::from_secs(300)), expected);
+        assert!(tracker.bursts.is_empty());
+    }
+}
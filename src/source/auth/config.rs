@@ -0,0 +1,41 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::source::OverflowPolicy;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Input {
+    File,
+    Journald,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct AuthLogSourceConfig {
+    pub input: Input,
+    pub path: PathBuf,
+    pub logins: bool,
+    pub sudo: bool,
+    pub sessions: bool,
+    pub burst_window: u64,
+    pub burst_threshold: usize,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for AuthLogSourceConfig {
+    fn default() -> Self {
+        AuthLogSourceConfig {
+            input: Input::File,
+            path: PathBuf::from("/var/log/auth.log"),
+            logins: true,
+            sudo: true,
+            sessions: false,
+            burst_window: 300,
+            burst_threshold: 5,
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+}
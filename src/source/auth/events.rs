@@ -0,0 +1,278 @@
+use regex::Regex;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthEvent {
+    Accepted {
+        user: String,
+        ip: String,
+        method: String,
+    },
+    Failed {
+        user: String,
+        ip: String,
+        method: String,
+        invalid: bool,
+    },
+    InvalidUser {
+        user: String,
+        ip: String,
+    },
+    Sudo {
+        user: String,
+        target: String,
+        command: String,
+    },
+    SudoFailed {
+        user: String,
+        target: String,
+        command: String,
+        attempts: String,
+    },
+    SessionOpened {
+        service: String,
+        user: String,
+    },
+}
+
+/// Line of a syslog file, like /var/log/auth.log
+#[derive(Debug, PartialEq)]
+pub struct SyslogLine {
+    pub host: String,
+    pub program: String,
+    pub message: String,
+}
+
+/// Recognises sshd and sudo messages, including PAM session ones
+pub struct EventParser {
+    syslog: Regex,
+    accepted: Regex,
+    failed: Regex,
+    invalid_user: Regex,
+    sudo: Regex,
+    session: Regex,
+}
+
+impl EventParser {
+    pub fn new() -> Self {
+        let regex = |pattern| Regex::new(pattern).unwrap();
+
+        EventParser {
+            syslog: regex(concat!(
+                r"^(?:[A-Z][a-z]{2} +\d+ \d{2}:\d{2}:\d{2}|\S+) (?P<host>\S+) ",
+                r"(?P<program>[^\s\[:]+)(?:\[\d+\])?: (?P<message>.*)$",
+            )),
+            accepted: regex(
+                r"^Accepted (?P<method>\S+) for (?P<user>\S+) from (?P<ip>\S+) port \d+",
+            ),
+            failed: regex(concat!(
+                r"^Failed (?P<method>\S+) for (?P<invalid>invalid user )?(?P<user>\S+) ",
+                r"from (?P<ip>\S+) port \d+",
+            )),
+            invalid_user: regex(r"^Invalid user (?P<user>\S*) from (?P<ip>\S+)"),
+            sudo: regex(concat!(
+                r"^\s*(?P<user>\S+) : (?:(?P<attempts>\d+) incorrect password attempts? ; )?",
+                r"TTY=\S+ ; PWD=.*? ; USER=(?P<target>\S+) ; (?:.*? ; )?COMMAND=(?P<command>.*)$",
+            )),
+            session: regex(concat!(
+                r"^pam_unix\((?P<service>[^:]+):session\): session opened for user ",
+                r"(?P<user>[^\s(]+)",
+            )),
+        }
+    }
+    pub fn parse_syslog_line(&self, line: &str) -> Option<SyslogLine> {
+        let captures = self.syslog.captures(line)?;
+
+        Some(SyslogLine {
+            host: captures["host"].to_owned(),
+            program: captures["program"].to_owned(),
+            message: captures["message"].to_owned(),
+        })
+    }
+    pub fn parse(&self, program: &str, message: &str) -> Option<AuthEvent> {
+        match program {
+            "sshd" | "sshd-session" => self
+                .parse_sshd(message)
+                .or_else(|| self.parse_session(message)),
+            "sudo" => self
+                .parse_sudo(message)
+                .or_else(|| self.parse_session(message)),
+            _ => None,
+        }
+    }
+    fn parse_sshd(&self, message: &str) -> Option<AuthEvent> {
+        if let Some(captures) = self.accepted.captures(message) {
+            return Some(AuthEvent::Accepted {
+                user: captures["user"].to_owned(),
+                ip: captures["ip"].to_owned(),
+                method: captures["method"].to_owned(),
+            });
+        }
+        if let Some(captures) = self.failed.captures(message) {
+            return Some(AuthEvent::Failed {
+                user: captures["user"].to_owned(),
+                ip: captures["ip"].to_owned(),
+                method: captures["method"].to_owned(),
+                invalid: captures.name("invalid").is_some(),
+            });
+        }
+
+        let captures = self.invalid_user.captures(message)?;
+        Some(AuthEvent::InvalidUser {
+            user: captures["user"].to_owned(),
+            ip: captures["ip"].to_owned(),
+        })
+    }
+    fn parse_sudo(&self, message: &str) -> Option<AuthEvent> {
+        let captures = self.sudo.captures(message)?;
+        let (user, target) = (captures["user"].to_owned(), captures["target"].to_owned());
+        let command = captures["command"].to_owned();
+
+        let event = match captures.name("attempts") {
+            Some(attempts) => AuthEvent::SudoFailed {
+                user,
+                target,
+                command,
+                attempts: attempts.as_str().to_owned(),
+            },
+            None => AuthEvent::Sudo {
+                user,
+                target,
+                command,
+            },
+        };
+
+        Some(event)
+    }
+    fn parse_session(&self, message: &str) -> Option<AuthEvent> {
+        let captures = self.session.captures(message)?;
+
+        Some(AuthEvent::SessionOpened {
+            service: captures["service"].to_owned(),
+            user: captures["user"].to_owned(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthEvent, EventParser, SyslogLine};
+
+    #[test]
+    fn main() {
+        let parser = EventParser::new();
+        let cases = vec![
+            (
+                "sshd",
+                "Accepted publickey for root from 203.0.113.7 port 52214 ssh2: ED25519 SHA256:abc",
+                AuthEvent::Accepted {
+                    user: String::from("root"),
+                    ip: String::from("203.0.113.7"),
+                    method: String::from("publickey"),
+                },
+            ),
+            (
+                "sshd",
+                "Failed password for invalid user admin from 198.51.100.4 port 4242 ssh2",
+                AuthEvent::Failed {
+                    user: String::from("admin"),
+                    ip: String::from("198.51.100.4"),
+                    method: String::from("password"),
+                    invalid: true,
+                },
+            ),
+            (
+                "sshd",
+                "Failed password for root from 198.51.100.4 port 4243 ssh2",
+                AuthEvent::Failed {
+                    user: String::from("root"),
+                    ip: String::from("198.51.100.4"),
+                    method: String::from("password"),
+                    invalid: false,
+                },
+            ),
+            (
+                "sshd",
+                "Invalid user oracle from 198.51.100.4 port 4244",
+                AuthEvent::InvalidUser {
+                    user: String::from("oracle"),
+                    ip: String::from("198.51.100.4"),
+                },
+            ),
+            (
+                "sshd-session",
+                "Failed publickey for git from 198.51.100.4 port 4245 ssh2: RSA SHA256:abc",
+                AuthEvent::Failed {
+                    user: String::from("git"),
+                    ip: String::from("198.51.100.4"),
+                    method: String::from("publickey"),
+                    invalid: false,
+                },
+            ),
+            (
+                "sudo",
+                "   alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/apt upgrade",
+                AuthEvent::Sudo {
+                    user: String::from("alice"),
+                    target: String::from("root"),
+                    command: String::from("/usr/bin/apt upgrade"),
+                },
+            ),
+            (
+                "sudo",
+                "bob : 3 incorrect password attempts ; TTY=pts/1 ; PWD=/tmp ; USER=root ; COMMAND=/bin/sh",
+                AuthEvent::SudoFailed {
+                    user: String::from("bob"),
+                    target: String::from("root"),
+                    command: String::from("/bin/sh"),
+                    attempts: String::from("3"),
+                },
+            ),
+            (
+                "sshd",
+                "pam_unix(sshd:session): session opened for user root(uid=0) by (uid=0)",
+                AuthEvent::SessionOpened {
+                    service: String::from("sshd"),
+                    user: String::from("root"),
+                },
+            ),
+        ];
+
+        for (program, message, expected) in cases {
+            assert_eq!(
+                parser.parse(program, message),
+                Some(expected),
+                "{}",
+                message
+            );
+        }
+
+        assert_eq!(
+            parser.parse("sshd", "Connection closed by 203.0.113.7 port 52214"),
+            None
+        );
+        assert_eq!(
+            parser.parse("cron", "Accepted password for root from 1.2.3.4 port 1"),
+            None
+        );
+    }
+
+    #[test]
+    fn syslog() {
+        let parser = EventParser::new();
+        let expected = Some(SyslogLine {
+            host: String::from("web"),
+            program: String::from("sshd"),
+            message: String::from("Invalid user oracle from 198.51.100.4 port 4244"),
+        });
+
+        let line =
+            "Jan  2 03:04:05 web sshd[1234]: Invalid user oracle from 198.51.100.4 port 4244";
+        assert_eq!(parser.parse_syslog_line(line), expected);
+
+        let line = "2021-01-02T03:04:05.123456+00:00 web sshd[1234]: Invalid user oracle from 198.51.100.4 port 4244";
+        assert_eq!(parser.parse_syslog_line(line), expected);
+
+        let line = "Jan  2 03:04:05 web sudo: pam_unix(sudo:session): session closed for user root";
+        assert_eq!(parser.parse_syslog_line(line).unwrap().program, "sudo");
+    }
+}
@@ -0,0 +1,65 @@
+use anyhow::Result;
+use std::time::Duration;
+#[cfg(feature = "ls_journald")]
+use systemd::journal::{Journal, JournalFiles};
+
+use super::events::{EventParser, SyslogLine};
+use crate::source::tail::FileTail;
+
+pub enum LineInput {
+    File(FileTail),
+    #[cfg(feature = "ls_journald")]
+    Journald(Journal),
+}
+
+impl LineInput {
+    #[cfg(feature = "ls_journald")]
+    pub fn journald() -> Result<Self> {
+        let mut journal = Journal::open(JournalFiles::All, false, true)?;
+        journal.match_add("SYSLOG_IDENTIFIER", "sshd")?;
+        journal.match_or()?;
+        // OpenSSH 9.8+ logs the authentication from a separate sshd-session process
+        journal.match_add("SYSLOG_IDENTIFIER", "sshd-session")?;
+        journal.match_or()?;
+        journal.match_add("SYSLOG_IDENTIFIER", "sudo")?;
+        journal.seek_tail()?;
+
+        Ok(LineInput::Journald(journal))
+    }
+    #[cfg(not(feature = "ls_journald"))]
+    pub fn journald() -> Result<Self> {
+        Err(anyhow::anyhow!(
+            "Journald input of auth log source requires ls_journald feature"
+        ))
+    }
+    /// Waits up to `timeout` for new lines
+    pub fn read(&mut self, parser: &EventParser, timeout: Duration) -> Result<Vec<SyslogLine>> {
+        match self {
+            LineInput::File(tail) => {
+                let lines = tail
+                    .wait_lines(timeout)?
+                    .iter()
+                    .filter_map(|line| parser.parse_syslog_line(line))
+                    .collect();
+                Ok(lines)
+            }
+            #[cfg(feature = "ls_journald")]
+            LineInput::Journald(journal) => {
+                let mut lines = Vec::new();
+                while let Some(mut entry) = journal.next_entry()? {
+                    let line = SyslogLine {
+                        host: entry.remove("_HOSTNAME").unwrap_or_default(),
+                        program: entry.remove("SYSLOG_IDENTIFIER").unwrap_or_default(),
+                        message: entry.remove("MESSAGE").unwrap_or_default(),
+                    };
+                    lines.push(line);
+                }
+                if lines.is_empty() {
+                    journal.wait(Some(timeout))?;
+                }
+
+                Ok(lines)
+            }
+        }
+    }
+}
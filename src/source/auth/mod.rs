@@ -0,0 +1,313 @@
+use anyhow::Result;
+use futures::{
+    channel::oneshot::{self, Sender as OneshotSender},
+    executor,
+};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::source::{
+    tail::FileTail, BlockingReceiver, BlockingSender, Level, LogRecord, LogSource, LogSourceStream,
+};
+
+mod bursts;
+mod config;
+mod events;
+mod input;
+pub use self::config::AuthLogSourceConfig;
+use self::{
+    bursts::{BurstSummary, BurstTracker},
+    config::Input,
+    events::{AuthEvent, EventParser, SyslogLine},
+    input::LineInput,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct AuthLogSource {
+    receiver: BlockingReceiver,
+}
+
+impl AuthLogSource {
+    pub fn new(config: AuthLogSourceConfig) -> Result<Self> {
+        let (init_tx, init_rx) = oneshot::channel();
+        let (record_tx, record_rx) = BlockingSender::channel(config.buffer, config.overflow);
+
+        thread::spawn(move || run_inner(config, init_tx, record_tx));
+
+        match executor::block_on(init_rx).unwrap() {
+            Err(error) => Err(error),
+            Ok(_) => Ok(AuthLogSource {
+                receiver: record_rx,
+            }),
+        }
+    }
+}
+
+impl LogSource for AuthLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        Box::pin(self.receiver)
+    }
+}
+
+struct AuthLogSourceInner {
+    input: LineInput,
+    parser: EventParser,
+    bursts: BurstTracker,
+    burst_window: Duration,
+    logins: bool,
+    sudo: bool,
+    sessions: bool,
+}
+
+impl AuthLogSourceInner {
+    fn new(config: AuthLogSourceConfig) -> Result<Self> {
+        let input = match config.input {
+            Input::File => LineInput::File(FileTail::new(config.path, true)?),
+            Input::Journald => LineInput::journald()?,
+        };
+        let burst_window = Duration::from_secs(config.burst_window);
+
+        Ok(AuthLogSourceInner {
+            input,
+            parser: EventParser::new(),
+            bursts: BurstTracker::new(burst_window, config.burst_threshold),
+            burst_window,
+            logins: config.logins,
+            sudo: config.sudo,
+            sessions: config.sessions,
+        })
+    }
+    fn next_records(&mut self) -> Result<Vec<LogRecord>> {
+        let lines = self.input.read(&self.parser, POLL_INTERVAL)?;
+        let now = Instant::now();
+
+        let mut records = Vec::new();
+        for line in lines {
+            let event = match self.parser.parse(&line.program, &line.message) {
+                Some(event) => event,
+                None => continue,
+            };
+
+            match &event {
+                AuthEvent::Failed {
+                    user,
+                    ip,
+                    method,
+                    invalid,
+                } => self.bursts.failed(ip, user, method, *invalid, now),
+                AuthEvent::InvalidUser { user, ip } => self.bursts.invalid_user(ip, user, now),
+                _ => {}
+            }
+            if let Some(record) = self.event_to_record(event, line) {
+                records.push(record);
+            }
+        }
+
+        let summaries = self.bursts.expired(now);
+        records.extend(
+            summaries
+                .into_iter()
+                .map(|summary| self.summary_to_record(summary)),
+        );
+
+        Ok(records)
+    }
+    fn event_to_record(&self, event: AuthEvent, line: SyslogLine) -> Option<LogRecord> {
+        let record = match event {
+            AuthEvent::Accepted { user, ip, method } if self.logins => {
+                let level = if user == "root" {
+                    Level::Warning
+                } else {
+                    Level::Notice
+                };
+
+                LogRecord::new(format!("SSH login of {} from {}", user, ip), line.message)
+                    .with_level(level)
+                    .with_metadata("event", "accepted")
+                    .with_metadata("user", user)
+                    .with_metadata("ip", ip)
+                    .with_metadata("method", method)
+            }
+            AuthEvent::Sudo {
+                user,
+                target,
+                command,
+            } if self.sudo => LogRecord::new(format!("sudo by {}", user), command.clone())
+                .with_level(Level::Notice)
+                .with_metadata("event", "sudo")
+                .with_metadata("user", user)
+                .with_metadata("target_user", target)
+                .with_metadata("command", command),
+            AuthEvent::SudoFailed {
+                user,
+                target,
+                command,
+                attempts,
+            } if self.sudo => {
+                let body = format!("{} incorrect password attempts: {}", attempts, command);
+
+                LogRecord::new(format!("Failed sudo by {}", user), body)
+                    .with_level(Level::Warning)
+                    .with_metadata("event", "sudo_failed")
+                    .with_metadata("user", user)
+                    .with_metadata("target_user", target)
+                    .with_metadata("command", command)
+            }
+            AuthEvent::SessionOpened { service, user } if self.sessions => {
+                LogRecord::new(format!("Session opened for {}", user), line.message)
+                    .with_level(Level::Info)
+                    .with_metadata("event", "session")
+                    .with_metadata("user", user)
+                    .with_metadata("service", service)
+            }
+            _ => return None,
+        };
+
+        if line.host.is_empty() {
+            Some(record)
+        } else {
+            Some(record.with_metadata("host", line.host))
+        }
+    }
+    fn summary_to_record(&self, summary: BurstSummary) -> LogRecord {
+        let title = format!(
+            "{} failed logins from {} in {}",
+            summary.count,
+            summary.ip,
+            format_window(self.burst_window)
+        );
+        let (users, methods) = (summary.users.join(", "), summary.methods.join(", "));
+        let body = if methods.is_empty() {
+            format!("Users: {}", users)
+        } else {
+            format!("Users: {}\nMethods: {}", users, methods)
+        };
+
+        LogRecord::new(title, body)
+            .with_level(Level::Warning)
+            .with_metadata("event", "failed_burst")
+            .with_metadata("ip", summary.ip)
+            .with_metadata("count", summary.count.to_string())
+            .with_metadata("users", users)
+            .with_metadata("methods", methods)
+    }
+}
+
+fn format_window(window: Duration) -> String {
+    let seconds = window.as_secs();
+
+    match (seconds / 3600, seconds / 60, seconds % 60) {
+        (hours, minutes, 0) if hours > 0 && minutes == hours * 60 => format!("{}h", hours),
+        (_, minutes, 0) if minutes > 0 => format!("{}m", minutes),
+        _ => format!("{}s", seconds),
+    }
+}
+
+fn run_inner(
+    config: AuthLogSourceConfig,
+    init_tx: OneshotSender<Result<()>>,
+    mut record_tx: BlockingSender,
+) {
+    let mut inner = match AuthLogSourceInner::new(config) {
+        Ok(inner) => {
+            init_tx.send(Ok(())).unwrap();
+            inner
+        }
+        Err(error) => {
+            init_tx.send(Err(error)).unwrap();
+            return;
+        }
+    };
+
+    loop {
+        let results = match inner.next_records() {
+            Ok(records) => records.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        };
+
+        for result in results {
+            if record_tx.send(result).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+    };
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{AuthLogSource, AuthLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let path = env::temp_dir().join("logram_test_auth.log");
+        fs::write(&path, "").unwrap();
+
+        let config = AuthLogSourceConfig {
+            path: path.clone(),
+            burst_window: 1,
+            burst_threshold: 2,
+            ..Default::default()
+        };
+
+        let source = AuthLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+
+        let lines = [
+            "Jan  2 03:04:05 web sshd[10]: Accepted publickey for root from 203.0.113.7 port 52214 ssh2",
+            "Jan  2 03:04:06 web sshd[11]: Invalid user oracle from 198.51.100.4 port 4242",
+            "Jan  2 03:04:07 web sshd[11]: Failed password for invalid user oracle from 198.51.100.4 port 4242 ssh2",
+            "Jan  2 03:04:08 web sshd[12]: Failed password for root from 198.51.100.4 port 4243 ssh2",
+            "Jan  2 03:04:09 web sshd[13]: pam_unix(sshd:session): session opened for user root by (uid=0)",
+            "Jan  2 03:04:10 web sudo:    alice : TTY=pts/0 ; PWD=/home/alice ; USER=root ; COMMAND=/usr/bin/id",
+        ];
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        for line in &lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+
+        let actual: Vec<LogRecord> = stream.take(3).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new(
+                "SSH login of root from 203.0.113.7",
+                "Accepted publickey for root from 203.0.113.7 port 52214 ssh2",
+            )
+            .with_level(Level::Warning)
+            .with_metadata("event", "accepted")
+            .with_metadata("user", "root")
+            .with_metadata("ip", "203.0.113.7")
+            .with_metadata("method", "publickey")
+            .with_metadata("host", "web"),
+            LogRecord::new("sudo by alice", "/usr/bin/id")
+                .with_level(Level::Notice)
+                .with_metadata("event", "sudo")
+                .with_metadata("user", "alice")
+                .with_metadata("target_user", "root")
+                .with_metadata("command", "/usr/bin/id")
+                .with_metadata("host", "web"),
+            LogRecord::new(
+                "2 failed logins from 198.51.100.4 in 1s",
+                "Users: oracle, root\nMethods: password",
+            )
+            .with_level(Level::Warning)
+            .with_metadata("event", "failed_burst")
+            .with_metadata("ip", "198.51.100.4")
+            .with_metadata("count", "2")
+            .with_metadata("users", "oracle, root")
+            .with_metadata("methods", "password"),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+
+/// Exponentially growing delay between reconnection attempts
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Backoff {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+
+        delay
+    }
+    #[cfg(feature = "ls_exec")]
+    pub fn max_delay(&self) -> Duration {
+        self.max
+    }
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::Backoff;
+
+    #[test]
+    fn main() {
+        let secs = Duration::from_secs;
+        let mut backoff = Backoff::new(secs(1), secs(5));
+
+        let delays: Vec<Duration> = (0..5).map(|_| backoff.next_delay()).collect();
+        assert_eq!(delays, vec![secs(1), secs(2), secs(4), secs(5), secs(5)]);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), secs(1));
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use openssl::{
+    hash::MessageDigest,
+    ssl::{SslConnector, SslMethod, SslVerifyMode},
+    x509::{X509NameRef, X509Ref, X509},
+};
+use std::{
+    fmt::Write,
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub sans: Vec<String>,
+    pub not_after: DateTime<Utc>,
+    pub fingerprint: String,
+}
+
+impl CertInfo {
+    /// Reads the first certificate of a PEM file, which is the leaf one in chain files
+    pub fn from_pem(pem: &[u8]) -> Result<Self> {
+        let cert = X509::stack_from_pem(pem)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("No certificates found"))?;
+
+        Self::from_x509(&cert)
+    }
+    fn from_x509(cert: &X509Ref) -> Result<Self> {
+        let sans = cert
+            .subject_alt_names()
+            .map(|names| {
+                names
+                    .iter()
+                    .filter_map(|name| name.dnsname().map(str::to_owned))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // e.g. "Jan  2 03:04:05 2021 GMT"
+        let not_after = cert.not_after().to_string();
+        let not_after = NaiveDateTime::parse_from_str(&not_after, "%b %e %H:%M:%S %Y GMT")?;
+
+        let mut fingerprint = String::new();
+        for byte in cert.digest(MessageDigest::sha256())?.iter() {
+            write!(fingerprint, "{:02x}", byte)?;
+        }
+
+        Ok(CertInfo {
+            subject: format_name(cert.subject_name()),
+            issuer: format_name(cert.issuer_name()),
+            sans,
+            not_after: Utc.from_utc_datetime(&not_after),
+            fingerprint,
+        })
+    }
+    /// Whole days until expiry, negative when expired
+    pub fn days_left(&self, now: DateTime<Utc>) -> i64 {
+        let seconds = (self.not_after - now).num_seconds();
+
+        if seconds < 0 {
+            (seconds - 86399) / 86400
+        } else {
+            seconds / 86400
+        }
+    }
+    pub fn describe(&self) -> String {
+        format!(
+            "Subject: {}\nSANs: {}\nIssuer: {}\nExpires: {}",
+            self.subject,
+            self.sans.join(", "),
+            self.issuer,
+            self.not_after.format("%Y-%m-%d %H:%M:%S UTC")
+        )
+    }
+}
+
+fn format_name(name: &X509NameRef) -> String {
+    let entries: Vec<String> = name
+        .entries()
+        .filter_map(|entry| {
+            let key = entry.object().nid().short_name().ok()?;
+            let value = entry.data().as_utf8().ok()?;
+            Some(format!("{}={}", key, value))
+        })
+        .collect();
+
+    entries.join(", ")
+}
+
+/// Connects to a `host:port` TLS endpoint and returns its certificate,
+/// the certificate is not verified so expired ones are reported too
+pub fn fetch(endpoint: &str, timeout: Duration) -> Result<CertInfo> {
+    let host = match endpoint.rfind(':') {
+        Some(index) => &endpoint[..index],
+        None => return Err(anyhow!("Endpoint {} has no port", endpoint)),
+    };
+    let addr = endpoint
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow!("Failed to resolve {}", endpoint))?;
+
+    let stream = TcpStream::connect_timeout(&addr, timeout)?;
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+    builder.set_verify(SslVerifyMode::NONE);
+    let connector = builder.build();
+
+    let stream = connector
+        .configure()?
+        .verify_hostname(false)
+        .connect(host, stream)
+        .map_err(|error| anyhow!("TLS handshake with {} failed: {}", endpoint, error))?;
+    let cert = stream
+        .ssl()
+        .peer_certificate()
+        .ok_or_else(|| anyhow!("{} sent no certificate", endpoint))?;
+
+    CertInfo::from_x509(&cert)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use chrono::{TimeZone, Utc};
+    use openssl::{
+        asn1::Asn1Time,
+        bn::BigNum,
+        hash::MessageDigest,
+        pkey::{PKey, Private},
+        rsa::Rsa,
+        ssl::{SslAcceptor, SslMethod},
+        x509::{extension::SubjectAlternativeName, X509Name, X509},
+    };
+    use std::{net::TcpListener, thread, time::Duration};
+
+    use super::{fetch, CertInfo};
+
+    /// Self-signed certificate for `name` valid until the unix time `not_after`
+    pub fn generate(name: &str, not_after: i64) -> (X509, PKey<Private>) {
+        let key = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+
+        let mut subject = X509Name::builder().unwrap();
+        subject.append_entry_by_text("CN", name).unwrap();
+        subject.append_entry_by_text("O", "Logram").unwrap();
+        let subject = subject.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+        builder.set_serial_number(&serial).unwrap();
+        builder.set_subject_name(&subject).unwrap();
+        builder.set_issuer_name(&subject).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::from_unix(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::from_unix(not_after as _).unwrap())
+            .unwrap();
+        let sans = SubjectAlternativeName::new()
+            .dns(name)
+            .dns(&format!("www.{}", name))
+            .build(&builder.x509v3_context(None, None))
+            .unwrap();
+        builder.append_extension(sans).unwrap();
+        builder.sign(&key, MessageDigest::sha256()).unwrap();
+
+        (builder.build(), key)
+    }
+
+    #[test]
+    fn main() {
+        let not_after = Utc.ymd(2031, 1, 2).and_hms(3, 4, 5);
+        let (cert, _) = generate("example.com", not_after.timestamp());
+
+        let info = CertInfo::from_pem(&cert.to_pem().unwrap()).unwrap();
+        assert_eq!(info.subject, "CN=example.com, O=Logram");
+        assert_eq!(info.issuer, "CN=example.com, O=Logram");
+        assert_eq!(info.sans, vec!["example.com", "www.example.com"]);
+        assert_eq!(info.not_after, not_after);
+        assert_eq!(info.fingerprint.len(), 64);
+
+        assert_eq!(info.days_left(Utc.ymd(2031, 1, 1).and_hms(3, 4, 5)), 1);
+        assert_eq!(info.days_left(Utc.ymd(2031, 1, 1).and_hms(3, 4, 6)), 0);
+        assert_eq!(info.days_left(Utc.ymd(2031, 1, 2).and_hms(3, 4, 6)), -1);
+
+        assert!(CertInfo::from_pem(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn endpoint() {
+        let not_after = Utc.ymd(2031, 1, 2).and_hms(3, 4, 5);
+        let (cert, key) = generate("localhost", not_after.timestamp());
+
+        let mut acceptor = SslAcceptor::mozilla_intermediate(SslMethod::tls()).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        acceptor.set_certificate(&cert).unwrap();
+        let acceptor = acceptor.build();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let _ = acceptor.accept(stream);
+        });
+
+        let info = fetch(&endpoint, Duration::from_secs(5)).unwrap();
+        assert_eq!(info.sans, vec!["localhost", "www.localhost"]);
+        assert_eq!(info.not_after, not_after);
+
+        assert!(fetch("127.0.0.1", Duration::from_secs(1)).is_err());
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct CertsLogSourceConfig {
+    pub interval: u64,
+    pub poll: u64,
+    pub thresholds: Vec<u32>,
+    pub files: Vec<PathBuf>,
+    pub endpoints: Vec<String>,
+    pub timeout: u64,
+}
+
+impl Default for CertsLogSourceConfig {
+    fn default() -> Self {
+        CertsLogSourceConfig {
+            interval: 3600,
+            poll: 10,
+            thresholds: vec![30, 14, 7, 1],
+            files: Vec::new(),
+            endpoints: Vec::new(),
+            timeout: 5000,
+        }
+    }
+}
@@ -0,0 +1,82 @@
+/// Threshold which is crossed when the certificate has expired
+pub const EXPIRED: i64 = -1;
+
+/// Tracks which expiry thresholds (in days) were already reported for a certificate
+#[derive(Debug)]
+pub struct Expiry {
+    thresholds: Vec<i64>,
+    reported: Option<i64>,
+}
+
+impl Expiry {
+    pub fn new(thresholds: Vec<u32>) -> Self {
+        let mut thresholds: Vec<i64> = thresholds.into_iter().map(i64::from).collect();
+        // the expiration itself is always reported, even after the smallest threshold
+        thresholds.push(EXPIRED);
+        thresholds.sort_unstable();
+
+        Expiry {
+            thresholds,
+            reported: None,
+        }
+    }
+    /// Returns the threshold to report, if the certificate crossed a new one
+    pub fn check(&mut self, days_left: i64) -> Option<i64> {
+        // renewed since the last report
+        if let Some(reported) = self.reported {
+            if days_left > reported {
+                self.reported = None;
+            }
+        }
+
+        let crossed = self
+            .thresholds
+            .iter()
+            .copied()
+            .find(|threshold| days_left <= *threshold)?;
+
+        match self.reported {
+            Some(reported) if reported <= crossed => None,
+            _ => {
+                self.reported = Some(crossed);
+                Some(crossed)
+            }
+        }
+    }
+    pub fn reset(&mut self) {
+        self.reported = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expiry, EXPIRED};
+
+    #[test]
+    fn main() {
+        let mut expiry = Expiry::new(vec![30, 14, 7, 1]);
+
+        assert_eq!(expiry.check(90), None);
+        assert_eq!(expiry.check(30), Some(30));
+        assert_eq!(expiry.check(29), None);
+        assert_eq!(expiry.check(10), Some(14));
+        assert_eq!(expiry.check(3), Some(7));
+        assert_eq!(expiry.check(2), None);
+        assert_eq!(expiry.check(1), Some(1));
+        assert_eq!(expiry.check(0), None);
+        assert_eq!(expiry.check(-1), Some(EXPIRED));
+        assert_eq!(expiry.check(-6), None);
+
+        // renewed
+        assert_eq!(expiry.check(90), None);
+        assert_eq!(expiry.check(20), Some(30));
+
+        expiry.reset();
+        assert_eq!(expiry.check(20), Some(30));
+
+        // expired without crossing any threshold before
+        let mut expiry = Expiry::new(vec![30]);
+        assert_eq!(expiry.check(-5), Some(EXPIRED));
+        assert_eq!(expiry.check(-6), None);
+    }
+}
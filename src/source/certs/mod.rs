@@ -0,0 +1,237 @@
+use anyhow::Result;
+use chrono::Utc;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use tokio::{task, time::delay_for};
+
+use crate::source::{Level, LogRecord, LogSource, LogSourceStream};
+
+mod cert;
+mod config;
+mod expiry;
+pub use self::config::CertsLogSourceConfig;
+use self::{cert::CertInfo, expiry::Expiry};
+
+#[derive(Debug, Clone)]
+enum Location {
+    File(PathBuf),
+    Endpoint(String),
+}
+
+struct Watched {
+    name: String,
+    location: Location,
+    info: Option<CertInfo>,
+    expiry: Expiry,
+}
+
+impl Watched {
+    async fn load(&self, timeout: Duration) -> Result<CertInfo> {
+        match self.location.clone() {
+            Location::File(path) => CertInfo::from_pem(&fs::read(path)?),
+            Location::Endpoint(endpoint) => {
+                task::spawn_blocking(move || cert::fetch(&endpoint, timeout)).await?
+            }
+        }
+    }
+    fn update(&mut self, info: CertInfo) -> Vec<LogRecord> {
+        let mut records = Vec::new();
+
+        let changed = match &self.info {
+            Some(old) => old.fingerprint != info.fingerprint,
+            None => false,
+        };
+        if changed {
+            self.expiry.reset();
+
+            let record = self
+                .record(format!("Certificate {} changed", self.name), &info)
+                .with_level(Level::Notice)
+                .with_metadata("fingerprint", info.fingerprint.clone());
+            records.push(record);
+        }
+
+        let days_left = info.days_left(Utc::now());
+        if self.expiry.check(days_left).is_some() {
+            let (title, level) = match days_left {
+                days if days < 0 => (
+                    format!("Certificate {} has expired", self.name),
+                    Level::Error,
+                ),
+                1 => (
+                    format!("Certificate {} expires in 1 day", self.name),
+                    Level::Warning,
+                ),
+                days => (
+                    format!("Certificate {} expires in {} days", self.name, days),
+                    Level::Warning,
+                ),
+            };
+
+            let record = self
+                .record(title, &info)
+                .with_level(level)
+                .with_metadata("days_left", days_left.to_string());
+            records.push(record);
+        }
+
+        self.info = Some(info);
+        records
+    }
+    fn record(&self, title: String, info: &CertInfo) -> LogRecord {
+        LogRecord::new(title, info.describe())
+            .with_metadata("source", self.name.clone())
+            .with_metadata("subject", info.subject.clone())
+            .with_metadata("issuer", info.issuer.clone())
+            .with_metadata("sans", info.sans.join(", "))
+            .with_metadata("not_after", info.not_after.to_rfc3339())
+    }
+}
+
+pub struct CertsLogSource {
+    interval: Duration,
+    poll: Duration,
+    timeout: Duration,
+    watched: Vec<Watched>,
+}
+
+impl CertsLogSource {
+    pub fn new(config: CertsLogSourceConfig) -> Result<Self> {
+        let files = config
+            .files
+            .into_iter()
+            .map(|path| (path.display().to_string(), Location::File(path)));
+        let endpoints = config
+            .endpoints
+            .into_iter()
+            .map(|endpoint| (endpoint.clone(), Location::Endpoint(endpoint)));
+
+        let thresholds = config.thresholds;
+        let watched = files
+            .chain(endpoints)
+            .map(|(name, location)| Watched {
+                name,
+                location,
+                info: None,
+                expiry: Expiry::new(thresholds.clone()),
+            })
+            .collect();
+
+        Ok(CertsLogSource {
+            interval: Duration::from_secs(config.interval),
+            poll: Duration::from_secs(config.poll),
+            timeout: Duration::from_millis(config.timeout),
+            watched,
+        })
+    }
+}
+
+impl LogSource for CertsLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+/// Checks everything each `interval`, and the files each `poll` to notice changes early
+async fn run(mut source: CertsLogSource, mut tx: Sender<Result<LogRecord>>) {
+    let mut next_check = Instant::now();
+
+    loop {
+        let full = Instant::now() >= next_check;
+        if full {
+            next_check = Instant::now() + source.interval;
+        }
+
+        for watched in &mut source.watched {
+            if !full && matches!(watched.location, Location::Endpoint(_)) {
+                continue;
+            }
+
+            let results = match watched.load(source.timeout).await {
+                Ok(info) => watched.update(info).into_iter().map(Ok).collect(),
+                // the file may be in the middle of being replaced, report on full checks only
+                Err(error) if full => {
+                    let error = error.context(format!("Failed to check {}", watched.name));
+                    vec![Err(error)]
+                }
+                Err(_) => continue,
+            };
+
+            for result in results {
+                if tx.send(result).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        delay_for(source.poll).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+    use futures::StreamExt;
+    use std::{env, fs};
+
+    use crate::source::{Level, LogSource};
+
+    use super::{cert::tests::generate, CertsLogSource, CertsLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let path = env::temp_dir().join("logram_test_certs.pem");
+        let not_after = Utc::now() + Duration::days(10) + Duration::hours(1);
+        let (cert, _) = generate("example.com", not_after.timestamp());
+        fs::write(&path, cert.to_pem().unwrap()).unwrap();
+
+        let config = CertsLogSourceConfig {
+            poll: 1,
+            files: vec![path.clone()],
+            ..Default::default()
+        };
+
+        let source = CertsLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let name = path.display().to_string();
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(
+            record.title,
+            format!("Certificate {} expires in 10 days", name)
+        );
+        assert_eq!(record.level, Some(Level::Warning));
+        assert_eq!(record.metadata["subject"], "CN=example.com, O=Logram");
+        assert_eq!(record.metadata["sans"], "example.com, www.example.com");
+        assert_eq!(record.metadata["days_left"], "10");
+        assert!(record
+            .body
+            .starts_with("Subject: CN=example.com, O=Logram\n"));
+
+        let not_after = Utc::now() - Duration::hours(1);
+        let (cert, _) = generate("example.org", not_after.timestamp());
+        fs::write(&path, cert.to_pem().unwrap()).unwrap();
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, format!("Certificate {} changed", name));
+        assert_eq!(record.level, Some(Level::Notice));
+        assert_eq!(record.metadata["subject"], "CN=example.org, O=Logram");
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, format!("Certificate {} has expired", name));
+        assert_eq!(record.level, Some(Level::Error));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -12,10 +12,83 @@ use super::journald::JournaldLogSourceConfig as JournaldConfig;
 #[cfg(feature = "ls_docker")]
 use super::docker::DockerLogSourceConfig as DockerConfig;
 
+#[cfg(feature = "ls_syslog")]
+use super::syslog::SyslogLogSourceConfig as SyslogConfig;
+
+#[cfg(feature = "ls_http")]
+use super::http::HttpLogSourceConfig as HttpConfig;
+
+#[cfg(feature = "ls_alertmanager")]
+use super::alertmanager::AlertmanagerLogSourceConfig as AlertmanagerConfig;
+
+#[cfg(feature = "ls_exec")]
+use super::exec::ExecLogSourceConfig as ExecConfig;
+
+#[cfg(feature = "ls_kmsg")]
+use super::kmsg::KmsgLogSourceConfig as KmsgConfig;
+
+#[cfg(feature = "ls_pipe")]
+use super::pipe::PipeLogSourceConfig as PipeConfig;
+
+#[cfg(feature = "ls_gelf")]
+use super::gelf::GelfLogSourceConfig as GelfConfig;
+
+#[cfg(feature = "ls_sysmon")]
+use super::sysmon::SysmonLogSourceConfig as SysmonConfig;
+
+#[cfg(feature = "ls_process")]
+use super::process::ProcessLogSourceConfig as ProcessConfig;
+
+#[cfg(feature = "ls_auth")]
+use super::auth::AuthLogSourceConfig as AuthConfig;
+
+#[cfg(feature = "ls_health")]
+use super::health::HealthLogSourceConfig as HealthConfig;
+
+#[cfg(feature = "ls_certs")]
+use super::certs::CertsLogSourceConfig as CertsConfig;
+
+#[cfg(feature = "ls_jobs")]
+use super::jobs::JobsLogSourceConfig as JobsConfig;
+
+#[cfg(feature = "ls_containerd")]
+use super::containerd::ContainerdLogSourceConfig as ContainerdConfig;
+
+#[cfg(feature = "ls_kubernetes")]
+use super::kubernetes::KubernetesLogSourceConfig as KubernetesConfig;
+
+#[cfg(feature = "ls_otlp")]
+use super::otlp::OtlpLogSourceConfig as OtlpConfig;
+
+#[cfg(feature = "ls_stdin")]
+use super::stdin::StdinLogSourceConfig as StdinConfig;
+
+#[cfg(feature = "ls_audit")]
+use super::audit::AuditLogSourceConfig as AuditConfig;
+
 fn default_enabled() -> bool {
     false
 }
 
+/// What a blocking log source does when the record channel is full
+#[cfg(any(
+    feature = "ls_filesystem",
+    feature = "ls_journald",
+    feature = "ls_kmsg",
+    feature = "ls_auth",
+    feature = "ls_jobs",
+    feature = "ls_audit"
+))]
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum OverflowPolicy {
+    /// Wait until there is a free slot in the channel
+    #[default]
+    Block,
+    /// Drop the record and report the number of dropped records later
+    Drop,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct LogSourceConfig<T> {
     #[serde(default = "default_enabled")]
@@ -34,6 +107,7 @@ impl<T: Default> Default for LogSourceConfig<T> {
 }
 
 #[derive(Default, Debug, Deserialize)]
+#[serde(default)]
 pub struct LogSourcesConfig {
     #[cfg(feature = "ls_counter")]
     pub counter: LogSourceConfig<CounterConfig>,
@@ -43,4 +117,40 @@ pub struct LogSourcesConfig {
     pub journald: LogSourceConfig<JournaldConfig>,
     #[cfg(feature = "ls_docker")]
     pub docker: LogSourceConfig<DockerConfig>,
+    #[cfg(feature = "ls_syslog")]
+    pub syslog: LogSourceConfig<SyslogConfig>,
+    #[cfg(feature = "ls_http")]
+    pub http: LogSourceConfig<HttpConfig>,
+    #[cfg(feature = "ls_alertmanager")]
+    pub alertmanager: LogSourceConfig<AlertmanagerConfig>,
+    #[cfg(feature = "ls_exec")]
+    pub exec: LogSourceConfig<ExecConfig>,
+    #[cfg(feature = "ls_kmsg")]
+    pub kmsg: LogSourceConfig<KmsgConfig>,
+    #[cfg(feature = "ls_pipe")]
+    pub pipe: LogSourceConfig<PipeConfig>,
+    #[cfg(feature = "ls_gelf")]
+    pub gelf: LogSourceConfig<GelfConfig>,
+    #[cfg(feature = "ls_sysmon")]
+    pub sysmon: LogSourceConfig<SysmonConfig>,
+    #[cfg(feature = "ls_process")]
+    pub process: LogSourceConfig<ProcessConfig>,
+    #[cfg(feature = "ls_auth")]
+    pub auth: LogSourceConfig<AuthConfig>,
+    #[cfg(feature = "ls_health")]
+    pub health: LogSourceConfig<HealthConfig>,
+    #[cfg(feature = "ls_certs")]
+    pub certs: LogSourceConfig<CertsConfig>,
+    #[cfg(feature = "ls_jobs")]
+    pub jobs: LogSourceConfig<JobsConfig>,
+    #[cfg(feature = "ls_containerd")]
+    pub containerd: LogSourceConfig<ContainerdConfig>,
+    #[cfg(feature = "ls_kubernetes")]
+    pub kubernetes: LogSourceConfig<KubernetesConfig>,
+    #[cfg(feature = "ls_otlp")]
+    pub otlp: LogSourceConfig<OtlpConfig>,
+    #[cfg(feature = "ls_stdin")]
+    pub stdin: LogSourceConfig<StdinConfig>,
+    #[cfg(feature = "ls_audit")]
+    pub audit: LogSourceConfig<AuditConfig>,
 }
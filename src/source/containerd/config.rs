@@ -0,0 +1,23 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::source::ParserConfig;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ContainerdLogSourceConfig {
+    pub path: PathBuf,
+    pub interval: u64,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
+}
+
+impl Default for ContainerdLogSourceConfig {
+    fn default() -> Self {
+        ContainerdLogSourceConfig {
+            path: PathBuf::from("/var/log/containers"),
+            interval: 1000,
+            parser: ParserConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::time::Duration;
+use tokio::time::delay_for;
+
+use crate::source::{
+    cri::{CriEntry, CriLogs},
+    LogRecord, LogSource, LogSourceStream, Parser,
+};
+
+mod config;
+pub use self::config::ContainerdLogSourceConfig;
+
+/// Reads the logs of containerd containers from the CRI log files
+pub struct ContainerdLogSource {
+    logs: CriLogs,
+    interval: Duration,
+    parser: Parser,
+}
+
+impl ContainerdLogSource {
+    pub fn new(config: ContainerdLogSourceConfig) -> Result<Self> {
+        Ok(ContainerdLogSource {
+            logs: CriLogs::new(config.path)?,
+            interval: Duration::from_millis(config.interval),
+            parser: Parser::new(config.parser)?,
+        })
+    }
+}
+
+impl LogSource for ContainerdLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+fn entry_to_record(entry: CriEntry) -> LogRecord {
+    let container = entry.container;
+    let mut record = LogRecord::new(format!("{} container", container.name), entry.message)
+        .with_timestamp(entry.timestamp)
+        .with_metadata("stream", entry.stream);
+
+    if let Some(pod) = container.pod {
+        record = record.with_metadata("pod", pod);
+    }
+    if let Some(namespace) = container.namespace {
+        record = record.with_metadata("namespace", namespace);
+    }
+    if let Some(id) = container.id {
+        record = record.with_metadata("container_id", id);
+    }
+
+    record
+}
+
+async fn run(mut source: ContainerdLogSource, mut tx: Sender<Result<LogRecord>>) {
+    loop {
+        let results: Vec<Result<LogRecord>> = match source.logs.poll() {
+            Ok(entries) => entries
+                .into_iter()
+                .map(|entry| Ok(source.parser.parse_record(entry_to_record(entry))))
+                .collect(),
+            Err(error) => vec![Err(error)],
+        };
+
+        for result in results {
+            if tx.send(result).await.is_err() {
+                return;
+            }
+        }
+
+        delay_for(source.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+    };
+
+    use crate::source::{parser::Format, Level, LogRecord, LogSource, ParserConfig};
+
+    use super::{ContainerdLogSource, ContainerdLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let dir = env::temp_dir().join("logram_test_containerd");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let path = dir.join(format!("web-5d8f7_shop_api-{}.log", id));
+        fs::write(&path, "").unwrap();
+
+        let config = ContainerdLogSourceConfig {
+            path: dir.clone(),
+            interval: 100,
+            parser: ParserConfig {
+                format: Format::Json,
+                ..Default::default()
+            },
+        };
+
+        let source = ContainerdLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        writeln!(
+            file,
+            r#"2021-01-02T03:04:05Z stderr F {{"level":"error","msg":"payment failed"}}"#
+        )
+        .unwrap();
+
+        let record = stream.next().await.unwrap().unwrap();
+        let expected = LogRecord::new("api container", "payment failed")
+            .with_level(Level::Error)
+            .with_timestamp(Utc.ymd(2021, 1, 2).and_hms(3, 4, 5))
+            .with_metadata("stream", "stderr")
+            .with_metadata("pod", "web-5d8f7")
+            .with_metadata("namespace", "shop")
+            .with_metadata("container_id", id);
+        assert_eq!(record, expected);
+
+        let config = ContainerdLogSourceConfig {
+            path: dir.join("nonexistent"),
+            ..Default::default()
+        };
+        assert!(ContainerdLogSource::new(config).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
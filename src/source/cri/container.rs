@@ -0,0 +1,97 @@
+use std::path::Path;
+
+/// Container a log file belongs to, known from the path of the file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContainerInfo {
+    pub name: String,
+    pub pod: Option<String>,
+    pub namespace: Option<String>,
+    pub id: Option<String>,
+}
+
+impl ContainerInfo {
+    /// Supports both `/var/log/containers/<pod>_<namespace>_<container>-<id>.log`
+    /// and `/var/log/pods/<namespace>_<pod>_<uid>/<container>/<restart>.log`
+    pub fn from_path(path: &Path) -> Self {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if let Some(info) = Self::from_containers_name(&stem) {
+            return info;
+        }
+        if let Some(info) = Self::from_pods_path(path) {
+            return info;
+        }
+
+        ContainerInfo {
+            name: stem,
+            pod: None,
+            namespace: None,
+            id: None,
+        }
+    }
+    fn from_containers_name(stem: &str) -> Option<Self> {
+        let (rest, id) = stem.rsplit_once('-')?;
+        let mut parts = rest.splitn(3, '_');
+        let (pod, namespace, name) = (parts.next()?, parts.next()?, parts.next()?);
+
+        if id.len() != 64 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        Some(ContainerInfo {
+            name: name.to_owned(),
+            pod: Some(pod.to_owned()),
+            namespace: Some(namespace.to_owned()),
+            id: Some(id.to_owned()),
+        })
+    }
+    fn from_pods_path(path: &Path) -> Option<Self> {
+        let container_dir = path.parent()?;
+        let pod_dir = container_dir.parent()?.file_name()?.to_string_lossy();
+
+        let mut parts = pod_dir.splitn(3, '_');
+        let (namespace, pod, _uid) = (parts.next()?, parts.next()?, parts.next()?);
+
+        Some(ContainerInfo {
+            name: container_dir.file_name()?.to_string_lossy().into_owned(),
+            pod: Some(pod.to_owned()),
+            namespace: Some(namespace.to_owned()),
+            id: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::ContainerInfo;
+
+    #[test]
+    fn main() {
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let path = format!("/var/log/containers/web-5d8f7_shop_nginx-{}.log", id);
+        let expected = ContainerInfo {
+            name: String::from("nginx"),
+            pod: Some(String::from("web-5d8f7")),
+            namespace: Some(String::from("shop")),
+            id: Some(String::from(id)),
+        };
+        assert_eq!(ContainerInfo::from_path(Path::new(&path)), expected);
+
+        let path = "/var/log/pods/shop_web-5d8f7_0c2e4b1a-uid/nginx/0.log";
+        let expected = ContainerInfo {
+            id: None,
+            ..expected
+        };
+        assert_eq!(ContainerInfo::from_path(Path::new(path)), expected);
+
+        let path = "/var/log/app.log";
+        let info = ContainerInfo::from_path(Path::new(path));
+        assert_eq!(info.name, "app");
+        assert_eq!(info.pod, None);
+    }
+}
@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+
+/// Line of a CRI log file: `<timestamp> <stream> <P|F> <message>`
+#[derive(Debug, PartialEq)]
+pub struct CriLine {
+    pub timestamp: DateTime<Utc>,
+    pub stream: String,
+    /// The message continues on the next line of the same stream
+    pub partial: bool,
+    pub message: String,
+}
+
+pub fn parse(line: &str) -> Option<CriLine> {
+    let mut parts = line.splitn(4, ' ');
+
+    let timestamp = DateTime::parse_from_rfc3339(parts.next()?).ok()?;
+    let stream = parts.next()?;
+    // the tag may have more colon separated attributes in the future
+    let partial = match parts.next()?.split(':').next()? {
+        "P" => true,
+        "F" => false,
+        _ => return None,
+    };
+
+    Some(CriLine {
+        timestamp: timestamp.with_timezone(&Utc),
+        stream: stream.to_owned(),
+        partial,
+        message: parts.next().unwrap_or_default().to_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::{parse, CriLine};
+
+    #[test]
+    fn main() {
+        let line = "2021-01-02T03:04:05.123456789+00:00 stderr P connection refused, retrying";
+        let expected = CriLine {
+            timestamp: Utc.ymd(2021, 1, 2).and_hms_nano(3, 4, 5, 123_456_789),
+            stream: String::from("stderr"),
+            partial: true,
+            message: String::from("connection refused, retrying"),
+        };
+        assert_eq!(parse(line), Some(expected));
+
+        let line = parse("2021-01-02T03:04:05Z stdout F").unwrap();
+        assert_eq!(line.message, "");
+        assert!(!line.partial);
+
+        assert_eq!(parse("2021-01-02T03:04:05Z stdout X message"), None);
+        assert_eq!(parse("plain text"), None);
+    }
+}
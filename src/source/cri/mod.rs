@@ -0,0 +1,197 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+
+mod container;
+mod line;
+pub use self::container::ContainerInfo;
+use crate::source::tail::FileTail;
+
+/// Complete message of a container, partial lines already joined
+#[derive(Debug, PartialEq)]
+pub struct CriEntry {
+    pub path: PathBuf,
+    pub container: ContainerInfo,
+    pub stream: String,
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+}
+
+struct LogFile {
+    tail: FileTail,
+    container: ContainerInfo,
+    /// Partial messages by stream
+    partial: HashMap<String, String>,
+}
+
+/// Follows the CRI log files (`*.log`) in a directory and its subdirectories.
+/// Files existing at start are read from the end, files appeared later from the start
+pub struct CriLogs {
+    root: PathBuf,
+    files: HashMap<PathBuf, LogFile>,
+}
+
+impl CriLogs {
+    pub fn new(root: PathBuf) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(anyhow!("{} is not a directory", root.display()));
+        }
+
+        let mut logs = CriLogs {
+            root,
+            files: HashMap::new(),
+        };
+        logs.scan(true)?;
+
+        Ok(logs)
+    }
+    /// Reads new messages of all files
+    pub fn poll(&mut self) -> Result<Vec<CriEntry>> {
+        self.scan(false)?;
+
+        let mut entries = Vec::new();
+        for (path, file) in &mut self.files {
+            for line in file.tail.read_lines()? {
+                let line = match line::parse(&line) {
+                    Some(line) => line,
+                    None => continue,
+                };
+
+                let partial = file.partial.entry(line.stream.clone()).or_default();
+                partial.push_str(&line.message);
+                if line.partial {
+                    continue;
+                }
+
+                entries.push(CriEntry {
+                    path: path.clone(),
+                    container: file.container.clone(),
+                    stream: line.stream,
+                    timestamp: line.timestamp,
+                    message: std::mem::take(partial),
+                });
+            }
+        }
+
+        entries.sort_by_key(|entry| entry.timestamp);
+        Ok(entries)
+    }
+    /// Starts following new files and forgets the removed ones
+    fn scan(&mut self, from_end: bool) -> Result<()> {
+        let mut paths = Vec::new();
+        find_logs(&self.root, &mut paths)?;
+
+        self.files.retain(|path, _| paths.contains(path));
+        for path in paths {
+            if self.files.contains_key(&path) {
+                continue;
+            }
+
+            let file = LogFile {
+                tail: FileTail::new(path.clone(), from_end)?,
+                container: ContainerInfo::from_path(&path),
+                partial: HashMap::new(),
+            };
+            self.files.insert(path, file);
+        }
+
+        Ok(())
+    }
+}
+
+fn find_logs(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+
+        // follows symlinks, as /var/log/containers consists of them
+        if path.is_dir() {
+            find_logs(&path, paths)?;
+        } else if path.extension() == Some(OsStr::new("log")) {
+            paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+        os::unix::fs::symlink,
+    };
+
+    use super::CriLogs;
+
+    #[test]
+    fn main() {
+        let dir = env::temp_dir().join("logram_test_cri");
+        let _ = fs::remove_dir_all(&dir);
+        let pods = dir.join("pods/shop_web-5d8f7_0c2e4b1a/nginx");
+        let containers = dir.join("containers");
+        fs::create_dir_all(&pods).unwrap();
+        fs::create_dir_all(&containers).unwrap();
+
+        let log_path = pods.join("0.log");
+        fs::write(&log_path, "2021-01-02T03:04:05Z stdout F before start\n").unwrap();
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let link = containers.join(format!("web-5d8f7_shop_nginx-{}.log", id));
+        symlink(&log_path, &link).unwrap();
+
+        let mut logs = CriLogs::new(containers.clone()).unwrap();
+        assert!(logs.poll().unwrap().is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&log_path).unwrap();
+        let lines = [
+            "2021-01-02T03:04:06Z stderr P very ",
+            "2021-01-02T03:04:06Z stdout F GET /",
+            "2021-01-02T03:04:07Z stderr F long line",
+        ];
+        for line in &lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+
+        let entries = logs.poll().unwrap();
+        let messages: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.stream.as_str(), entry.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![("stdout", "GET /"), ("stderr", "very long line")]
+        );
+
+        let entry = &entries[1];
+        assert_eq!(entry.path, link);
+        assert_eq!(entry.container.name, "nginx");
+        assert_eq!(entry.container.namespace.as_deref(), Some("shop"));
+        assert_eq!(entry.timestamp, Utc.ymd(2021, 1, 2).and_hms(3, 4, 7));
+
+        // a new container is read from the start
+        let other = dir.join("pods/shop_db-0_9a8b7c6d/postgres");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("0.log"), "2021-01-02T03:04:08Z stdout F ready\n").unwrap();
+
+        let mut logs = CriLogs::new(dir.join("pods")).unwrap();
+        assert!(logs.poll().unwrap().is_empty());
+        fs::write(
+            other.join("1.log"),
+            "2021-01-02T03:04:09Z stdout F restarted\n",
+        )
+        .unwrap();
+        let entries = logs.poll().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].container.name, "postgres");
+        assert_eq!(entries[0].message, "restarted");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
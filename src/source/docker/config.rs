@@ -1,27 +1,120 @@
 use serde::Deserialize;
+use std::{env, fs, os::unix::fs::MetadataExt};
 
-#[derive(Debug, Deserialize)]
+use super::{filter::ContainerFilter, lifecycle::LifecycleConfig};
+use crate::source::ParserConfig;
+
+#[derive(Debug, Deserialize, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum Transport {
     Local,
     Unix,
     Http,
+    Podman,
+}
+
+impl Transport {
+    /// Address to connect to when it isn't configured
+    pub fn default_addr(&self) -> String {
+        match self {
+            Transport::Podman => {
+                // the owner of /proc/self is the user logram runs as
+                let uid = fs::metadata("/proc/self")
+                    .map(|meta| meta.uid())
+                    .unwrap_or(0);
+                podman_socket(env::var("XDG_RUNTIME_DIR").ok(), uid)
+            }
+            _ => String::from("unix:///var/run/docker.sock"),
+        }
+    }
+}
+
+/// Podman runs a system socket for root and a socket in the runtime directory
+/// for rootless users
+pub fn podman_socket(runtime_dir: Option<String>, uid: u32) -> String {
+    if uid == 0 {
+        return String::from("unix:///run/podman/podman.sock");
+    }
+
+    let runtime_dir = runtime_dir.unwrap_or_else(|| format!("/run/user/{}", uid));
+    format!("unix://{}/podman/podman.sock", runtime_dir)
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Streams {
+    Both,
+    Stdout,
+    Stderr,
+}
+
+impl Streams {
+    pub fn stdout(self) -> bool {
+        self != Streams::Stderr
+    }
+    pub fn stderr(self) -> bool {
+        self != Streams::Stdout
+    }
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(default)]
 pub struct DockerLogSourceConfig {
     pub transport: Transport,
-    pub addr: String,
+    pub addr: Option<String>,
     pub timeout: u64,
+    pub include: Vec<ContainerFilter>,
+    pub exclude: Vec<ContainerFilter>,
+    pub lifecycle: LifecycleConfig,
+    pub streams: Streams,
+    pub reconnect_delay: u64,
+    pub reconnect_max_delay: u64,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
 }
 
 impl Default for DockerLogSourceConfig {
     fn default() -> DockerLogSourceConfig {
         DockerLogSourceConfig {
             transport: Transport::Local,
-            addr: String::from("unix:///var/run/docker.sock"),
+            addr: None,
             timeout: 120,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            lifecycle: LifecycleConfig::default(),
+            streams: Streams::Both,
+            reconnect_delay: 1,
+            reconnect_max_delay: 60,
+            parser: ParserConfig::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{podman_socket, Transport};
+
+    #[test]
+    fn main() {
+        assert_eq!(
+            podman_socket(Some(String::from("/run/user/1000")), 1000),
+            "unix:///run/user/1000/podman/podman.sock"
+        );
+        assert_eq!(
+            podman_socket(None, 1001),
+            "unix:///run/user/1001/podman/podman.sock"
+        );
+        assert_eq!(
+            podman_socket(Some(String::from("/run/user/0")), 0),
+            "unix:///run/podman/podman.sock"
+        );
+
+        assert_eq!(
+            Transport::Local.default_addr(),
+            "unix:///var/run/docker.sock"
+        );
+        assert!(Transport::Podman
+            .default_addr()
+            .ends_with("/podman/podman.sock"));
+    }
+}
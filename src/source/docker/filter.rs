@@ -0,0 +1,158 @@
+use bollard::models::{ContainerSummaryInner, SystemEventsResponse};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::source::glob::glob_match;
+
+static COMPOSE_PROJECT_LABEL: &str = "com.docker.compose.project";
+static COMPOSE_SERVICE_LABEL: &str = "com.docker.compose.service";
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ContainerFilter {
+    pub name: Option<String>,
+    pub image: Option<String>,
+    pub label: Option<String>,
+    pub compose_project: Option<String>,
+    pub compose_service: Option<String>,
+}
+
+impl ContainerFilter {
+    /// Checks that the container satisfies all of the specified conditions
+    fn matches(&self, container: &Container) -> bool {
+        let label = |key: &str| container.labels.get(key).map(String::as_str);
+
+        if let Some(pattern) = &self.name {
+            if !glob_match(pattern, &container.name) {
+                return false;
+            }
+        }
+        if let Some(pattern) = &self.image {
+            if !glob_match(pattern, &container.image) {
+                return false;
+            }
+        }
+        if let Some(selector) = &self.label {
+            let matched = match selector.split_once('=') {
+                Some((key, value)) => label(key) == Some(value),
+                None => label(selector).is_some(),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        if let Some(project) = &self.compose_project {
+            if label(COMPOSE_PROJECT_LABEL) != Some(project) {
+                return false;
+            }
+        }
+        if let Some(service) = &self.compose_service {
+            if label(COMPOSE_SERVICE_LABEL) != Some(service) {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContainerFilters {
+    pub include: Vec<ContainerFilter>,
+    pub exclude: Vec<ContainerFilter>,
+}
+
+impl ContainerFilters {
+    pub fn matches(&self, container: &Container) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|f| f.matches(container));
+        let excluded = self.exclude.iter().any(|f| f.matches(container));
+
+        included && !excluded
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Container {
+    pub name: String,
+    pub image: String,
+    pub labels: HashMap<String, String>,
+}
+
+impl Container {
+    pub fn from_summary(summary: ContainerSummaryInner) -> Option<Self> {
+        let name = summary.names?.into_iter().next()?;
+
+        Some(Container {
+            name: String::from(name.trim_start_matches('/')),
+            image: summary.image.unwrap_or_default(),
+            labels: summary.labels.unwrap_or_default(),
+        })
+    }
+    /// Docker puts the container labels into event attributes along with name and image
+    pub fn from_event(event: SystemEventsResponse) -> Option<Self> {
+        let mut labels = event.actor?.attributes?;
+        let name = labels.remove("name")?;
+        let image = labels.remove("image").unwrap_or_default();
+
+        Some(Container {
+            name,
+            image,
+            labels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{Container, ContainerFilter, ContainerFilters};
+
+    #[test]
+    fn filters() {
+        let mut labels = HashMap::new();
+        labels.insert(String::from("com.example.alert"), String::from("true"));
+        labels.insert(
+            String::from("com.docker.compose.project"),
+            String::from("shop"),
+        );
+        let container = Container {
+            name: String::from("shop_api_1"),
+            image: String::from("shop/api:latest"),
+            labels,
+        };
+
+        let filter =
+            |name: Option<&str>, image: Option<&str>, label: Option<&str>| ContainerFilter {
+                name: name.map(String::from),
+                image: image.map(String::from),
+                label: label.map(String::from),
+                ..Default::default()
+            };
+        let compose = ContainerFilter {
+            compose_project: Some(String::from("shop")),
+            ..Default::default()
+        };
+
+        let no_filters = ContainerFilters::default();
+        assert!(no_filters.matches(&container));
+
+        let by_label = ContainerFilters {
+            include: vec![filter(None, None, Some("com.example.alert=true"))],
+            exclude: Vec::new(),
+        };
+        assert!(by_label.matches(&container));
+
+        let by_other_label = ContainerFilters {
+            include: vec![filter(None, None, Some("com.example.alert=false"))],
+            exclude: Vec::new(),
+        };
+        assert!(!by_other_label.matches(&container));
+
+        let excluded = ContainerFilters {
+            include: vec![compose],
+            exclude: vec![filter(Some("*_api_*"), Some("shop/*"), None)],
+        };
+        assert!(!excluded.matches(&container));
+    }
+}
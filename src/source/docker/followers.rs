@@ -0,0 +1,192 @@
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Log entries with the latest timestamp of a container
+#[derive(Debug, Clone, PartialEq)]
+pub struct LastSeen {
+    pub timestamp: DateTime<Utc>,
+    bodies: Vec<String>,
+}
+
+impl LastSeen {
+    /// Checks the entry read after resuming the logs, returns `true` for the entries
+    /// which were already sent. Docker resumes from the start of a second, so only
+    /// the entries up to the last seen one overlap
+    pub fn is_duplicate(&mut self, timestamp: DateTime<Utc>, body: &str) -> bool {
+        if timestamp < self.timestamp {
+            return true;
+        }
+        if timestamp > self.timestamp {
+            return false;
+        }
+
+        match self.bodies.iter().position(|seen| seen == body) {
+            Some(position) => {
+                self.bodies.remove(position);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    generation: u64,
+    active: HashMap<String, u64>,
+    last_seen: HashMap<String, LastSeen>,
+    /// When the followers without seen entries have ended
+    stopped: HashMap<String, DateTime<Utc>>,
+}
+
+impl Inner {
+    fn stop(&mut self, name: &str) {
+        self.active.remove(name);
+        if !self.last_seen.contains_key(name) {
+            self.stopped.insert(String::from(name), Utc::now());
+        }
+    }
+}
+
+/// Keeps track of followed container logs, so that the logs are
+/// resumed without duplicates after reconnecting to the daemon
+#[derive(Debug, Clone, Default)]
+pub struct LogFollowers {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl LogFollowers {
+    /// Marks the container logs as followed, returns `None` if they are already followed,
+    /// otherwise the generation of the follower, the last seen log entries, if any,
+    /// and the time to read the logs since
+    pub fn start(&self, name: &str) -> Option<(u64, Option<LastSeen>, DateTime<Utc>)> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.active.contains_key(name) {
+            return None;
+        }
+
+        let generation = inner.generation;
+        inner.active.insert(String::from(name), generation);
+
+        let last_seen = inner.last_seen.get(name).cloned();
+        let stopped = inner.stopped.remove(name);
+        let since = match (&last_seen, stopped) {
+            (Some(last_seen), _) => last_seen.timestamp,
+            (None, Some(stopped)) => stopped,
+            (None, None) => Utc::now(),
+        };
+
+        Some((generation, last_seen, since))
+    }
+    /// Checks that the follower wasn't replaced after reconnecting
+    pub fn is_current(&self, name: &str, generation: u64) -> bool {
+        self.inner.lock().unwrap().active.get(name) == Some(&generation)
+    }
+    pub fn stop(&self, name: &str, generation: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.active.get(name) == Some(&generation) {
+            inner.stop(name);
+        }
+    }
+    /// Forgets the followers of the lost connection, their streams may be still open
+    pub fn reset(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.generation += 1;
+        let active: Vec<String> = inner.active.keys().cloned().collect();
+        for name in active {
+            inner.stop(&name);
+        }
+    }
+    pub fn forget(&self, name: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.last_seen.remove(name);
+        inner.stopped.remove(name);
+    }
+    /// Remembers the log entry as the last seen one
+    pub fn seen(&self, name: &str, timestamp: DateTime<Utc>, body: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        let last_seen = inner
+            .last_seen
+            .entry(String::from(name))
+            .or_insert_with(|| LastSeen {
+                timestamp,
+                bodies: Vec::new(),
+            });
+
+        if timestamp > last_seen.timestamp {
+            last_seen.timestamp = timestamp;
+            last_seen.bodies.clear();
+        }
+        if timestamp == last_seen.timestamp {
+            last_seen.bodies.push(String::from(body));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::LogFollowers;
+
+    #[test]
+    fn main() {
+        let followers = LogFollowers::default();
+        let first = Utc.timestamp(1_600_000_000, 0);
+        let second = Utc.timestamp(1_600_000_001, 0);
+
+        let (generation, last_seen, _) = followers.start("app").unwrap();
+        assert_eq!((generation, last_seen), (0, None));
+        assert_eq!(followers.start("app"), None);
+        followers.seen("app", first, "a");
+        followers.seen("app", second, "b");
+        // distinct lines with the same timestamp
+        followers.seen("app", second, "c");
+        followers.stop("app", 0);
+
+        let (generation, last_seen, since) = followers.start("app").unwrap();
+        assert_eq!(since, second);
+        let mut last_seen = last_seen.unwrap();
+        assert!(last_seen.is_duplicate(first, "a"));
+        assert!(last_seen.is_duplicate(second, "b"));
+        assert!(!last_seen.is_duplicate(second, "d"));
+        assert!(last_seen.is_duplicate(second, "c"));
+        assert!(!last_seen.is_duplicate(second, "c"));
+        assert!(!last_seen.is_duplicate(Utc.timestamp(1_600_000_002, 0), "a"));
+
+        // the old follower is still running after reconnect
+        followers.reset();
+        assert!(!followers.is_current("app", generation));
+        let (new_generation, _, _) = followers.start("app").unwrap();
+        followers.stop("app", generation);
+        assert!(followers.is_current("app", new_generation));
+        followers.stop("app", new_generation);
+
+        followers.forget("app");
+        let (generation, last_seen, _) = followers.start("app").unwrap();
+        assert_eq!((generation, last_seen), (1, None));
+    }
+    #[test]
+    fn since_lost() {
+        let followers = LogFollowers::default();
+
+        // nothing was read before the connection was lost
+        followers.start("app").unwrap();
+        let lost = Utc::now();
+        followers.reset();
+
+        let (_, last_seen, since) = followers.start("app").unwrap();
+        assert_eq!(last_seen, None);
+        assert!(since >= lost && since <= Utc::now());
+        followers.stop("app", 1);
+
+        // the logs of a forgotten container are read from now
+        followers.forget("app");
+        let before = Utc::now();
+        let (_, _, since) = followers.start("app").unwrap();
+        assert!(since >= before);
+    }
+}
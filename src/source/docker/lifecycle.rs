@@ -0,0 +1,282 @@
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::source::{Level, LogRecord};
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct LifecycleConfig {
+    pub die: bool,
+    pub oom: bool,
+    pub health_status: bool,
+    pub restart: bool,
+    pub restart_threshold: usize,
+    pub restart_window: u64,
+    pub tail: usize,
+}
+
+impl LifecycleConfig {
+    pub fn is_enabled(&self, event: &LifecycleEvent) -> bool {
+        match event {
+            LifecycleEvent::Die { .. } => self.die,
+            LifecycleEvent::Oom => self.oom,
+            LifecycleEvent::Unhealthy => self.health_status,
+        }
+    }
+}
+
+impl Default for LifecycleConfig {
+    fn default() -> Self {
+        LifecycleConfig {
+            die: false,
+            oom: false,
+            health_status: false,
+            restart: false,
+            restart_threshold: 3,
+            restart_window: 300,
+            tail: 10,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum LifecycleEvent {
+    Die { exit_code: Option<i64> },
+    Oom,
+    Unhealthy,
+}
+
+impl LifecycleEvent {
+    pub fn parse(action: &str, exit_code: Option<&String>) -> Option<Self> {
+        match action {
+            "die" => {
+                let exit_code = exit_code.and_then(|code| code.parse().ok());
+
+                Some(LifecycleEvent::Die { exit_code })
+            }
+            "oom" => Some(LifecycleEvent::Oom),
+            "health_status: unhealthy" => Some(LifecycleEvent::Unhealthy),
+            _ => None,
+        }
+    }
+    pub fn title(&self, name: &str) -> String {
+        match self {
+            LifecycleEvent::Die {
+                exit_code: Some(code),
+            } => {
+                format!("{} container died with exit code {}", name, code)
+            }
+            LifecycleEvent::Die { exit_code: None } => format!("{} container died", name),
+            LifecycleEvent::Oom => format!("{} container ran out of memory", name),
+            LifecycleEvent::Unhealthy => format!("{} container is unhealthy", name),
+        }
+    }
+    /// OOM kills and failures are errors, other events are warnings
+    pub fn level(&self) -> Level {
+        match self {
+            LifecycleEvent::Die { exit_code: Some(0) } => Level::Info,
+            LifecycleEvent::Die { exit_code: Some(_) } | LifecycleEvent::Oom => Level::Error,
+            LifecycleEvent::Die { exit_code: None } | LifecycleEvent::Unhealthy => Level::Warning,
+        }
+    }
+    pub fn to_record(&self, name: &str, body: String) -> LogRecord {
+        let record = LogRecord::new(self.title(name), body)
+            .with_level(self.level())
+            .with_metadata("container", name);
+
+        match self {
+            LifecycleEvent::Die {
+                exit_code: Some(code),
+            } => record.with_metadata("exit_code", code.to_string()),
+            _ => record,
+        }
+    }
+}
+
+/// Counts containers restarts, a restart is a start of a container that died before,
+/// or a `restart` event which wasn't already counted this way
+pub struct RestartTracker {
+    threshold: usize,
+    window: Duration,
+    died: HashMap<String, Instant>,
+    counted: HashMap<String, Instant>,
+    restarts: HashMap<String, Vec<Instant>>,
+}
+
+impl RestartTracker {
+    pub fn new(config: &LifecycleConfig) -> Self {
+        RestartTracker {
+            threshold: config.restart_threshold,
+            window: Duration::from_secs(config.restart_window),
+            died: HashMap::new(),
+            counted: HashMap::new(),
+            restarts: HashMap::new(),
+        }
+    }
+    pub fn died(&mut self, name: &str, now: Instant) {
+        self.prune(now);
+        self.died.insert(String::from(name), now);
+    }
+    /// Returns the number of restarts if it has reached the threshold within the window
+    pub fn started(&mut self, name: &str, now: Instant) -> Option<usize> {
+        self.prune(now);
+        self.died.remove(name)?;
+
+        self.counted.insert(String::from(name), now);
+        self.count(name, now)
+    }
+    /// Same as `started`, for the `restart` event which Docker sends after the start
+    pub fn restarted(&mut self, name: &str, now: Instant) -> Option<usize> {
+        self.prune(now);
+        self.died.remove(name);
+        if self.counted.remove(name).is_some() {
+            return None;
+        }
+
+        self.count(name, now)
+    }
+    pub fn forget(&mut self, name: &str) {
+        self.died.remove(name);
+        self.counted.remove(name);
+        self.restarts.remove(name);
+    }
+    fn count(&mut self, name: &str, now: Instant) -> Option<usize> {
+        let restarts = self.restarts.entry(String::from(name)).or_default();
+        restarts.push(now);
+
+        if restarts.len() < self.threshold {
+            return None;
+        }
+
+        let count = restarts.len();
+        restarts.clear();
+
+        Some(count)
+    }
+    /// Drops everything older than the window, so short-lived containers don't pile up
+    fn prune(&mut self, now: Instant) {
+        let window = self.window;
+        let is_recent = |time: &Instant| now.duration_since(*time) < window;
+
+        self.died.retain(|_, time| is_recent(time));
+        self.counted.retain(|_, time| is_recent(time));
+        self.restarts.retain(|_, restarts| {
+            restarts.retain(is_recent);
+            !restarts.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::source::{Level, LogRecord};
+
+    use super::{LifecycleConfig, LifecycleEvent, RestartTracker};
+
+    #[test]
+    fn parse() {
+        let code = String::from("137");
+
+        assert_eq!(
+            LifecycleEvent::parse("die", Some(&code)),
+            Some(LifecycleEvent::Die {
+                exit_code: Some(137)
+            })
+        );
+        assert_eq!(
+            LifecycleEvent::parse("oom", None),
+            Some(LifecycleEvent::Oom)
+        );
+        assert_eq!(
+            LifecycleEvent::parse("health_status: unhealthy", None),
+            Some(LifecycleEvent::Unhealthy)
+        );
+        assert_eq!(LifecycleEvent::parse("health_status: healthy", None), None);
+        assert_eq!(LifecycleEvent::parse("attach", None), None);
+    }
+
+    #[test]
+    fn records() {
+        let died = LifecycleEvent::Die {
+            exit_code: Some(137),
+        };
+        let expected = LogRecord::new("web container died with exit code 137", "last line")
+            .with_level(Level::Error)
+            .with_metadata("container", "web")
+            .with_metadata("exit_code", "137");
+        assert_eq!(died.to_record("web", String::from("last line")), expected);
+
+        let stopped = LifecycleEvent::Die { exit_code: Some(0) };
+        assert_eq!(stopped.level(), Level::Info);
+
+        let expected = LogRecord::new("web container ran out of memory", "")
+            .with_level(Level::Error)
+            .with_metadata("container", "web");
+        assert_eq!(
+            LifecycleEvent::Oom.to_record("web", String::new()),
+            expected
+        );
+        assert_eq!(LifecycleEvent::Unhealthy.level(), Level::Warning);
+    }
+
+    #[test]
+    fn restarts() {
+        let config = LifecycleConfig {
+            restart_threshold: 3,
+            restart_window: 60,
+            ..Default::default()
+        };
+        let mut tracker = RestartTracker::new(&config);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(tracker.started("app", at(0)), None);
+
+        tracker.died("app", at(0));
+        assert_eq!(tracker.started("app", at(1)), None);
+        tracker.died("app", at(99));
+        assert_eq!(tracker.started("app", at(100)), None);
+        tracker.died("app", at(109));
+        assert_eq!(tracker.started("app", at(110)), None);
+        // the restart event after the start isn't counted twice
+        assert_eq!(tracker.restarted("app", at(110)), None);
+        tracker.died("app", at(119));
+        assert_eq!(tracker.started("app", at(120)), Some(3));
+
+        tracker.died("app", at(129));
+        assert_eq!(tracker.started("app", at(130)), None);
+
+        // restart events without the die and start
+        assert_eq!(tracker.restarted("web", at(130)), None);
+        assert_eq!(tracker.restarted("web", at(131)), None);
+        assert_eq!(tracker.restarted("web", at(132)), Some(3));
+    }
+
+    #[test]
+    fn pruning() {
+        let config = LifecycleConfig {
+            restart_window: 60,
+            ..Default::default()
+        };
+        let mut tracker = RestartTracker::new(&config);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        tracker.died("ci-job-1", at(0));
+        tracker.died("ci-job-2", at(0));
+        assert_eq!(tracker.started("ci-job-2", at(1)), None);
+        tracker.died("ci-job-3", at(10));
+        tracker.forget("ci-job-3");
+        assert_eq!(tracker.died.len(), 1);
+
+        tracker.died("app", at(100));
+        assert_eq!(tracker.died.len(), 1);
+        assert!(tracker.counted.is_empty());
+        assert!(tracker.restarts.is_empty());
+    }
+}
@@ -1,148 +1,419 @@
-use anyhow::Result;
+use anyhow::{anyhow, Error, Result};
 use bollard::{
-    container::{LogOutput, LogsOptions},
-    models::SystemEventsResponse,
+    container::{ListContainersOptions, LogOutput, LogsOptions},
     Docker, API_DEFAULT_VERSION,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use futures::{
-    channel::mpsc::{self as futures_mpsc, SendError, Sender},
-    future, Future, Stream, StreamExt,
+    channel::mpsc::{self as futures_mpsc, Sender},
+    future, SinkExt, Stream, StreamExt,
 };
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
 
-use crate::source::{LogRecord, LogSource, LogSourceStream};
+use crate::source::{Backoff, Level, LogRecord, LogSource, LogSourceStream, Parser};
 
 mod config;
-pub use self::config::{DockerLogSourceConfig, Transport};
+mod filter;
+mod followers;
+mod lifecycle;
+pub use self::config::{DockerLogSourceConfig, Streams, Transport};
+use self::{
+    filter::{Container, ContainerFilters},
+    followers::LogFollowers,
+    lifecycle::{LifecycleConfig, LifecycleEvent, RestartTracker},
+};
 
 type RecordSender = Sender<Result<LogRecord>>;
 
+enum ContainerEvent {
+    Start(String),
+    Restart(String),
+    Destroy(String),
+    Lifecycle(String, LifecycleEvent),
+}
+
 #[derive(Debug, Clone)]
 pub struct DockerLogSource {
     docker: Docker,
+    filters: ContainerFilters,
+    lifecycle: LifecycleConfig,
+    streams: Streams,
+    followers: LogFollowers,
+    parser: Parser,
+    reconnect_delay: Duration,
+    reconnect_max_delay: Duration,
 }
 
 impl DockerLogSource {
     pub fn new(config: DockerLogSourceConfig) -> Result<Self> {
         let version = API_DEFAULT_VERSION;
+        let addr = match config.addr {
+            Some(addr) => addr,
+            None => config.transport.default_addr(),
+        };
         let docker = match config.transport {
-            Transport::Local => Docker::connect_with_local(&config.addr, config.timeout, version)?,
-            Transport::Unix => Docker::connect_with_unix(&config.addr, config.timeout, version)?,
-            Transport::Http => Docker::connect_with_http(&config.addr, config.timeout, version)?,
+            Transport::Local => Docker::connect_with_local(&addr, config.timeout, version)?,
+            Transport::Unix | Transport::Podman => {
+                Docker::connect_with_unix(&addr, config.timeout, version)?
+            }
+            Transport::Http => Docker::connect_with_http(&addr, config.timeout, version)?,
+        };
+
+        let filters = ContainerFilters {
+            include: config.include,
+            exclude: config.exclude,
         };
 
-        Ok(DockerLogSource { docker })
+        Ok(DockerLogSource {
+            docker,
+            filters,
+            lifecycle: config.lifecycle,
+            streams: config.streams,
+            followers: LogFollowers::default(),
+            parser: Parser::new(config.parser)?,
+            reconnect_delay: Duration::from_secs(config.reconnect_delay),
+            reconnect_max_delay: Duration::from_secs(config.reconnect_max_delay),
+        })
+    }
+    /// Checks that the daemon is reachable. The version endpoint is used,
+    /// as bollard fails to decode the plain "OK" response of `/_ping`
+    async fn ping(&self) -> Result<()> {
+        self.docker.version().await?;
+
+        Ok(())
     }
     async fn runned_containers(&self) -> Result<Vec<String>> {
         let containers = self
             .docker
-            .list_containers::<String>(None)
+            .list_containers(None::<ListContainersOptions<String>>)
             .await?
             .into_iter()
             .filter(|ctr| ctr.state.as_deref() == Some("running"))
-            .filter_map(|ctr| ctr.names)
-            .filter_map(|names| names.into_iter().next())
-            .map(|name| String::from(name.trim_start_matches('/')))
+            .filter_map(Container::from_summary)
+            .filter(|ctr| self.filters.matches(ctr))
+            .map(|ctr| ctr.name)
             .collect();
 
         Ok(containers)
     }
-    fn running_containers(&self) -> impl Stream<Item = Result<String>> {
+    fn container_events(&self) -> impl Stream<Item = Result<ContainerEvent>> {
         let events_stream = self.docker.events::<String>(None);
+        let filters = self.filters.clone();
 
-        events_stream.filter_map(|entry| {
+        events_stream.filter_map(move |entry| {
             let resp = match entry {
                 Err(error) => Some(Err(error.into())),
                 Ok(event) => {
-                    let typ = event.typ.as_deref();
-                    let action = event.action.as_deref();
-
-                    if typ == Some("container") && action == Some("start") {
-                        container_name(event).map(Ok)
-                    } else {
-                        None
+                    if event.typ.as_deref() != Some("container") {
+                        return future::ready(None);
                     }
+
+                    let action = event.action.clone().unwrap_or_default();
+                    let exit_code = event
+                        .actor
+                        .as_ref()
+                        .and_then(|actor| actor.attributes.as_ref())
+                        .and_then(|attributes| attributes.get("exitCode").cloned());
+
+                    Container::from_event(event)
+                        .filter(|ctr| filters.matches(ctr))
+                        .and_then(|ctr| match action.as_str() {
+                            "start" => Some(ContainerEvent::Start(ctr.name)),
+                            "restart" => Some(ContainerEvent::Restart(ctr.name)),
+                            "destroy" => Some(ContainerEvent::Destroy(ctr.name)),
+                            _ => LifecycleEvent::parse(&action, exit_code.as_ref())
+                                .map(|event| ContainerEvent::Lifecycle(ctr.name, event)),
+                        })
+                        .map(Ok)
                 }
             };
 
             future::ready(resp)
         })
     }
-    async fn listen_runned_containers(&self, tx: RecordSender) -> Result<()> {
-        let runned_containers = self.runned_containers().await?;
+    /// Follows the logs and events until the connection to the daemon is lost
+    async fn listen(&self, tx: &mut RecordSender, restarts: &mut RestartTracker) -> Error {
+        let mut container_events = self.container_events();
+
+        // followers of the lost connection may never end, start them over
+        self.followers.reset();
+        let runned_containers = match self.runned_containers().await {
+            Ok(containers) => containers,
+            Err(error) => return error,
+        };
+        for name in runned_containers {
+            self.follow_logs(name, tx.clone());
+        }
 
-        for container in runned_containers {
-            tokio::spawn(listen_logs(self.docker.clone(), container, tx.clone()));
+        while let Some(event) = container_events.next().await {
+            let result = match event {
+                Ok(event) => self.handle_event(event, tx, restarts).await,
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = result {
+                return error;
+            }
         }
 
-        Ok(())
+        anyhow!("Docker events stream has ended")
     }
-    async fn listen_running_containers(&self, tx: RecordSender) -> Result<()> {
-        let mut running_containers = self.running_containers();
+    async fn handle_event(
+        &self,
+        event: ContainerEvent,
+        tx: &mut RecordSender,
+        restarts: &mut RestartTracker,
+    ) -> Result<()> {
+        match event {
+            ContainerEvent::Start(name) => {
+                let restarted = restarts.started(&name, Instant::now());
+                self.send_restarts(&name, restarted, tx).await?;
+
+                self.follow_logs(name, tx.clone());
+            }
+            ContainerEvent::Restart(name) => {
+                let restarted = restarts.restarted(&name, Instant::now());
+                self.send_restarts(&name, restarted, tx).await?;
+            }
+            ContainerEvent::Destroy(name) => {
+                self.followers.forget(&name);
+                restarts.forget(&name);
+            }
+            ContainerEvent::Lifecycle(name, event) => {
+                if let LifecycleEvent::Die { .. } = event {
+                    restarts.died(&name, Instant::now());
+                }
+
+                if self.lifecycle.is_enabled(&event) {
+                    // reading the last logs takes a while, don't hold up the events
+                    let tail = self.lifecycle.tail;
+                    let record = lifecycle_record(self.docker.clone(), name, event, tail);
+                    let mut tx = tx.clone();
 
-        while let Some(container) = running_containers.next().await {
-            tokio::spawn(listen_logs(self.docker.clone(), container?, tx.clone()));
+                    tokio::spawn(async move {
+                        let _ = tx.send(Ok(record.await)).await;
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+    async fn send_restarts(
+        &self,
+        name: &str,
+        restarted: Option<usize>,
+        tx: &mut RecordSender,
+    ) -> Result<()> {
+        if let (Some(count), true) = (restarted, self.lifecycle.restart) {
+            let title = format!("{} container is restarting", name);
+            let body = format!(
+                "Restarted {} times in {} seconds",
+                count, self.lifecycle.restart_window
+            );
+            let record = LogRecord::new(title, body)
+                .with_level(Level::Warning)
+                .with_metadata("container", name)
+                .with_metadata("restarts", count.to_string());
+            tx.send(Ok(record)).await?;
         }
 
         Ok(())
     }
+    fn follow_logs(&self, name: String, tx: RecordSender) {
+        let docker = self.docker.clone();
+        let followers = self.followers.clone();
+        let parser = self.parser.clone();
+
+        tokio::spawn(follow_logs(
+            docker,
+            name,
+            self.streams,
+            followers,
+            parser,
+            tx,
+        ));
+    }
+    async fn run(self, mut tx: RecordSender) {
+        let mut restarts = RestartTracker::new(&self.lifecycle);
+        let mut backoff = Backoff::new(self.reconnect_delay, self.reconnect_max_delay);
+        let mut unreachable = false;
+
+        loop {
+            let error = match self.ping().await {
+                Err(error) => error,
+                Ok(_) => {
+                    if unreachable {
+                        unreachable = false;
+                        backoff.reset();
+
+                        let title = String::from("Docker daemon recovered");
+                        if tx.send(Ok(LogRecord::only_title(title))).await.is_err() {
+                            return;
+                        }
+                    }
+
+                    self.listen(&mut tx, &mut restarts).await
+                }
+            };
+
+            if !unreachable {
+                unreachable = true;
+
+                let record = LogRecord::new("Docker daemon unreachable", error.to_string());
+                if tx.send(Ok(record)).await.is_err() {
+                    return;
+                }
+            }
+            if tx.is_closed() {
+                return;
+            }
+
+            delay_for(backoff.next_delay()).await;
+        }
+    }
 }
 
 impl LogSource for DockerLogSource {
     fn into_stream(self) -> LogSourceStream {
-        let (mut tx, rx) = futures_mpsc::channel(10);
+        let (tx, rx) = futures_mpsc::channel(10);
 
-        let source = self.clone();
-        let mut sender = tx.clone();
-        tokio::spawn(async move {
-            if let Err(error) = source.listen_runned_containers(sender.clone()).await {
-                sender.try_send(Err(error)).unwrap();
-            }
-        });
-
-        tokio::spawn(async move {
-            if let Err(error) = self.listen_running_containers(tx.clone()).await {
-                tx.try_send(Err(error)).unwrap();
-            }
-        });
+        tokio::spawn(self.run(tx));
 
         Box::pin(rx)
     }
 }
 
-fn container_name(event: SystemEventsResponse) -> Option<String> {
-    event.actor?.attributes?.get("name").cloned()
+async fn lifecycle_record(
+    docker: Docker,
+    name: String,
+    event: LifecycleEvent,
+    tail: usize,
+) -> LogRecord {
+    let body = match event {
+        LifecycleEvent::Die { .. } if tail > 0 => last_logs(&docker, &name, tail)
+            .await
+            .unwrap_or_else(|error| format!("Unable to read logs: {}", error)),
+        _ => String::new(),
+    };
+
+    event.to_record(&name, body)
 }
 
-fn entry_to_record(name: &str, body: LogOutput) -> LogRecord {
+async fn last_logs(docker: &Docker, name: &str, tail: usize) -> Result<String> {
+    let options: LogsOptions<String> = LogsOptions {
+        follow: false,
+        stdout: true,
+        stderr: true,
+        tail: tail.to_string(),
+        ..Default::default()
+    };
+
+    let mut lines = Vec::new();
+    let mut logs = docker.logs(name, Some(options));
+    while let Some(entry) = logs.next().await {
+        lines.push(entry?.to_string());
+    }
+
+    Ok(lines.concat().trim_end_matches('\n').to_string())
+}
+
+fn entry_to_record(name: &str, entry: LogOutput) -> LogRecord {
+    let (stream, message) = match entry {
+        LogOutput::StdOut { message } | LogOutput::Console { message } => ("stdout", message),
+        LogOutput::StdErr { message } => ("stderr", message),
+        LogOutput::StdIn { message } => ("stdin", message),
+    };
+
     let title = format!("{} container", name);
-    let body = body.to_string().trim_end_matches('\n').to_string();
+    let (timestamp, body) = strip_timestamps(&String::from_utf8_lossy(&message));
+    let record = LogRecord::new(title, body).with_metadata("stream", stream);
 
-    LogRecord::new(title, body)
+    match timestamp {
+        Some(timestamp) => record.with_timestamp(timestamp),
+        None => record,
+    }
 }
 
-fn listen_logs(
+/// Strips the RFC3339 timestamps which Docker puts at the start of each line,
+/// returns the first of them
+fn strip_timestamps(text: &str) -> (Option<DateTime<Utc>>, String) {
+    let mut timestamp = None;
+    let mut lines = Vec::new();
+
+    for line in text.trim_end_matches('\n').split('\n') {
+        let (prefix, rest) = line.split_once(' ').unwrap_or((line, ""));
+
+        match DateTime::parse_from_rfc3339(prefix) {
+            Ok(time) => {
+                timestamp.get_or_insert_with(|| time.with_timezone(&Utc));
+                lines.push(rest);
+            }
+            Err(_) => lines.push(line),
+        }
+    }
+
+    (timestamp, lines.join("\n"))
+}
+
+async fn follow_logs(
     docker: Docker,
     name: String,
-    sender: Sender<Result<LogRecord>>,
-) -> impl Future<Output = Result<(), SendError>> {
+    streams: Streams,
+    followers: LogFollowers,
+    parser: Parser,
+    mut sender: RecordSender,
+) {
+    let (generation, mut last_seen, since) = match followers.start(&name) {
+        Some(follower) => follower,
+        None => return,
+    };
+
     let options: LogsOptions<String> = LogsOptions {
         follow: true,
-        stdout: true,
-        stderr: true,
-        timestamps: false,
-        since: Utc::now().timestamp(),
+        stdout: streams.stdout(),
+        stderr: streams.stderr(),
+        timestamps: true,
+        since: since.timestamp(),
         ..Default::default()
     };
 
-    docker
-        .logs(&name, Some(options))
-        .map(move |entry| match entry {
-            Ok(body) => Ok(entry_to_record(&name, body)),
-            Err(error) => Err(error.into()),
-        })
-        .map(Ok)
-        .forward(sender)
+    let mut logs = docker.logs(&name, Some(options));
+    'logs: while let Some(entry) = logs.next().await {
+        if !followers.is_current(&name, generation) {
+            return;
+        }
+
+        let record = match entry {
+            Ok(entry) => entry_to_record(&name, entry),
+            Err(error) => {
+                let error = anyhow!("Logs of {} container: {}", name, error);
+                let _ = sender.send(Err(error)).await;
+                break;
+            }
+        };
+
+        if let Some(timestamp) = record.timestamp {
+            let is_duplicate = last_seen
+                .as_mut()
+                .is_some_and(|last_seen| last_seen.is_duplicate(timestamp, &record.body));
+            if is_duplicate {
+                continue;
+            }
+
+            last_seen = None;
+            followers.seen(&name, timestamp, &record.body);
+        }
+
+        for record in parser.parse_lines(record) {
+            if sender.send(Ok(record)).await.is_err() {
+                break 'logs;
+            }
+        }
+    }
+
+    followers.stop(&name, generation);
 }
 
 #[cfg(test)]
@@ -155,10 +426,20 @@ mod tests {
         Docker,
     };
     use futures::StreamExt;
+    use std::{env, fs, path::Path};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::UnixListener,
+    };
 
     use crate::source::{LogRecord, LogSource};
 
-    use super::{DockerLogSource, DockerLogSourceConfig, Transport};
+    use chrono::{TimeZone, Utc};
+
+    use super::{
+        filter::ContainerFilter, strip_timestamps, DockerLogSource, DockerLogSourceConfig,
+        Transport,
+    };
 
     #[tokio::test]
     async fn main() {
@@ -167,20 +448,98 @@ mod tests {
 
         let config = DockerLogSourceConfig {
             transport: Transport::Local,
-            addr: String::from("unix:///var/run/docker.sock"),
+            addr: Some(String::from("unix:///var/run/docker.sock")),
             timeout: 120,
+            include: vec![ContainerFilter {
+                name: Some(String::from("logram-*")),
+                ..Default::default()
+            }],
+            ..Default::default()
         };
 
         let source = DockerLogSource::new(config).unwrap();
         let stream = source.into_stream();
 
-        let actual: Vec<LogRecord> = stream.take(1).map(Result::unwrap).collect().await;
-        let expected: Vec<LogRecord> = vec![LogRecord::new("logram-test container", "log_entry")];
+        let mut actual: Vec<LogRecord> = stream.take(1).map(Result::unwrap).collect().await;
+        let expected: Vec<LogRecord> =
+            vec![LogRecord::new("logram-test container", "log_entry")
+                .with_metadata("stream", "stdout")];
 
         delete_container().await.unwrap();
+        for record in &mut actual {
+            assert!(record.timestamp.take().is_some());
+        }
         assert_eq!(actual, expected);
     }
 
+    /// Answers like Docker and Podman do, with one running container
+    async fn stub_daemon(path: &Path) {
+        let _ = fs::remove_file(path);
+        let mut listener = UnixListener::bind(path).unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                reader.read_line(&mut request).await.unwrap();
+
+                let body = if request.contains("/_ping ") {
+                    "OK"
+                } else if request.contains("/version ") {
+                    r#"{"Version":"20.10.0","ApiVersion":"1.40"}"#
+                } else if request.contains("/containers/json") {
+                    r#"[{"Id":"4f2a1c","Names":["/web"],"Image":"nginx","State":"running"}]"#
+                } else {
+                    "{}"
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                reader
+                    .get_mut()
+                    .write_all(response.as_bytes())
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    #[tokio::test]
+    async fn transport() {
+        let path = env::temp_dir().join("logram_test_docker.sock");
+        stub_daemon(&path).await;
+
+        for transport in &[Transport::Unix, Transport::Podman] {
+            let config = DockerLogSourceConfig {
+                transport: *transport,
+                addr: Some(format!("unix://{}", path.display())),
+                ..Default::default()
+            };
+
+            let source = DockerLogSource::new(config).unwrap();
+            source.ping().await.unwrap();
+            assert_eq!(source.runned_containers().await.unwrap(), vec!["web"]);
+        }
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn timestamps() {
+        let text = "2021-01-02T03:04:05.123456789Z first line\n2021-01-02T03:04:06Z second line\n";
+        let (timestamp, body) = strip_timestamps(text);
+        let expected_timestamp = Utc.ymd(2021, 1, 2).and_hms_nano(3, 4, 5, 123_456_789);
+
+        assert_eq!(timestamp, Some(expected_timestamp));
+        assert_eq!(body, "first line\nsecond line");
+
+        let (timestamp, body) = strip_timestamps("no timestamp here\n");
+        assert_eq!(timestamp, None);
+        assert_eq!(body, "no timestamp here");
+    }
+
     async fn create_container() -> Result<()> {
         let docker = Docker::connect_with_local_defaults()?;
 
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Command {
+    pub command: String,
+    pub title: Option<String>,
+    pub interval: Option<u64>,
+    pub timeout: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ExecLogSourceConfig {
+    pub commands: Vec<Command>,
+    pub restart_delay: u64,
+    pub restart_max_delay: u64,
+}
+
+impl Default for ExecLogSourceConfig {
+    fn default() -> Self {
+        ExecLogSourceConfig {
+            commands: Vec::new(),
+            restart_delay: 1,
+            restart_max_delay: 60,
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    future, SinkExt,
+};
+use std::{
+    os::unix::process::ExitStatusExt,
+    process::{ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process::Command as Process,
+    time::{self, delay_for},
+};
+
+use crate::source::{Backoff, Level, LogRecord, LogSource, LogSourceStream};
+
+mod config;
+pub use self::config::{Command, ExecLogSourceConfig};
+
+type RecordSender = Sender<Result<LogRecord>>;
+
+pub struct ExecLogSource {
+    commands: Vec<Command>,
+    restart_delay: Duration,
+    restart_max_delay: Duration,
+}
+
+impl ExecLogSource {
+    pub fn new(config: ExecLogSourceConfig) -> Self {
+        ExecLogSource {
+            commands: config.commands,
+            restart_delay: Duration::from_secs(config.restart_delay),
+            restart_max_delay: Duration::from_secs(config.restart_max_delay),
+        }
+    }
+}
+
+impl LogSource for ExecLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        for command in self.commands {
+            match command.interval {
+                Some(interval) => {
+                    let interval = Duration::from_secs(interval);
+                    tokio::spawn(run_scheduled(command, interval, tx.clone()));
+                }
+                None => {
+                    let backoff = Backoff::new(self.restart_delay, self.restart_max_delay);
+                    tokio::spawn(run_long_running(command, backoff, tx.clone()));
+                }
+            }
+        }
+
+        Box::pin(rx)
+    }
+}
+
+fn title(command: &Command) -> String {
+    command
+        .title
+        .clone()
+        .unwrap_or_else(|| command.command.clone())
+}
+
+fn process(command: &Command) -> Process {
+    let mut process = Process::new("sh");
+    process
+        .arg("-c")
+        .arg(&command.command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    process
+}
+
+fn status_to_record(title: String, status: ExitStatus, output: String) -> LogRecord {
+    let level = if status.success() {
+        Level::Info
+    } else {
+        Level::Error
+    };
+    let title = match (status.code(), status.signal()) {
+        (Some(code), _) => format!("{} exited with code {}", title, code),
+        (None, Some(signal)) => format!("{} was killed by signal {}", title, signal),
+        (None, None) => format!("{} exited", title),
+    };
+
+    let mut record = LogRecord::new(title, output).with_level(level);
+    if let Some(code) = status.code() {
+        record = record.with_metadata("exit_code", code.to_string());
+    }
+
+    record
+}
+
+async fn run_scheduled(command: Command, interval: Duration, mut tx: RecordSender) {
+    loop {
+        let closed = match run_once(&command).await.transpose() {
+            Some(result) => tx.send(result).await.is_err(),
+            None => tx.is_closed(),
+        };
+        if closed {
+            return;
+        }
+
+        delay_for(interval).await;
+    }
+}
+
+/// Runs the command and returns a record if the command has failed or printed something
+async fn run_once(command: &Command) -> Result<Option<LogRecord>> {
+    let output = process(command).output();
+    let output = match command.timeout {
+        Some(timeout) => time::timeout(Duration::from_secs(timeout), output).await??,
+        None => output.await?,
+    };
+
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    let text = text.trim_end_matches('\n').to_string();
+
+    if !output.status.success() {
+        return Ok(Some(status_to_record(title(command), output.status, text)));
+    }
+    if text.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(
+        LogRecord::new(title(command), text).with_level(Level::Info),
+    ))
+}
+
+async fn run_long_running(command: Command, mut backoff: Backoff, mut tx: RecordSender) {
+    loop {
+        let started = Instant::now();
+        let result = match run_following(&command, tx.clone()).await {
+            Ok(status) => Ok(status_to_record(title(&command), status, String::new())),
+            Err(error) => Err(error),
+        };
+
+        if tx.send(result).await.is_err() {
+            return;
+        }
+
+        if started.elapsed() >= backoff.max_delay() {
+            backoff.reset();
+        }
+        delay_for(backoff.next_delay()).await;
+    }
+}
+
+/// Runs the command and sends each line of its output until the command exits
+async fn run_following(command: &Command, tx: RecordSender) -> Result<ExitStatus> {
+    let mut child = process(command).spawn()?;
+    let title = title(command);
+
+    let stdout = child
+        .stdout
+        .take()
+        .map(|stdout| read_lines(stdout, title.clone(), "stdout", tx.clone()));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|stderr| read_lines(stderr, title.clone(), "stderr", tx.clone()));
+
+    future::join(future::join_all(stdout), future::join_all(stderr)).await;
+
+    Ok(child.await?)
+}
+
+async fn read_lines<R: AsyncRead + Unpin>(
+    reader: R,
+    title: String,
+    stream: &'static str,
+    mut tx: RecordSender,
+) {
+    let mut reader = BufReader::new(reader);
+
+    loop {
+        let mut buffer = Vec::new();
+        let record = match reader.read_until(b'\n', &mut buffer).await {
+            Ok(0) => return,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buffer)
+                    .trim_end_matches(&['\r', '\n'][..])
+                    .to_owned();
+                Ok(LogRecord::new(title.clone(), line).with_metadata("stream", stream))
+            }
+            Err(error) => {
+                // the error is sent once, as reading again would likely fail the same way
+                let _ = tx.send(Err(error.into())).await;
+                return;
+            }
+        };
+
+        if tx.send(record).await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{Command, ExecLogSource, ExecLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let config = ExecLogSourceConfig {
+            commands: vec![Command {
+                command: String::from("echo line1; printf 'line\\377\\r\\n'; exit 2"),
+                title: Some(String::from("script")),
+                interval: None,
+                timeout: None,
+            }],
+            ..Default::default()
+        };
+
+        let source = ExecLogSource::new(config);
+        let stream = source.into_stream();
+
+        let actual: Vec<LogRecord> = stream.take(3).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("script", "line1").with_metadata("stream", "stdout"),
+            LogRecord::new("script", "line\u{fffd}").with_metadata("stream", "stdout"),
+            LogRecord::new("script exited with code 2", "")
+                .with_level(Level::Error)
+                .with_metadata("exit_code", "2"),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn scheduled() {
+        let config = ExecLogSourceConfig {
+            commands: vec![
+                Command {
+                    command: String::from("true"),
+                    title: None,
+                    interval: Some(1),
+                    timeout: None,
+                },
+                Command {
+                    command: String::from("echo unhealthy; exit 1"),
+                    title: Some(String::from("health check")),
+                    interval: Some(1),
+                    timeout: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let source = ExecLogSource::new(config);
+        let stream = source.into_stream();
+
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        let record = LogRecord::new("health check exited with code 1", "unhealthy")
+            .with_level(Level::Error)
+            .with_metadata("exit_code", "1");
+
+        assert_eq!(actual, vec![record.clone(), record]);
+    }
+}
@@ -1,11 +1,17 @@
 use serde::Deserialize;
 use std::path::PathBuf;
 
+use crate::source::{OverflowPolicy, ParserConfig};
+
 #[derive(Debug, Deserialize)]
 #[serde(default)]
 pub struct FilesystemLogSourceConfig {
     pub delay: u64,
     pub entries: Vec<PathBuf>,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
 }
 
 impl Default for FilesystemLogSourceConfig {
@@ -13,6 +19,9 @@ impl Default for FilesystemLogSourceConfig {
         FilesystemLogSourceConfig {
             delay: 1_000,
             entries: Vec::new(),
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
+            parser: ParserConfig::default(),
         }
     }
 }
@@ -1,5 +1,4 @@
 use anyhow::Result;
-use futures::channel::mpsc as futures_mpsc;
 use notify::{watcher, DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher};
 use std::{
     sync::mpsc::{self, Receiver},
@@ -7,7 +6,9 @@ use std::{
     time::Duration,
 };
 
-use crate::source::{LogRecord, LogSource, LogSourceStream};
+use crate::source::{
+    BlockingSender, LogRecord, LogSource, LogSourceStream, OverflowPolicy, Parser,
+};
 
 mod config;
 mod reader;
@@ -18,6 +19,9 @@ pub struct FilesystemLogSource {
     watcher: RecommendedWatcher,
     receiver: Receiver<DebouncedEvent>,
     reader: AdditionReader,
+    parser: Parser,
+    buffer: usize,
+    overflow: OverflowPolicy,
 }
 
 impl FilesystemLogSource {
@@ -31,11 +35,15 @@ impl FilesystemLogSource {
         }
 
         let reader = AdditionReader::new(config.entries)?;
+        let parser = Parser::new(config.parser)?;
 
         Ok(FilesystemLogSource {
             watcher,
             receiver,
             reader,
+            parser,
+            buffer: config.buffer,
+            overflow: config.overflow,
         })
     }
     fn next_record(&mut self) -> Result<LogRecord> {
@@ -85,16 +93,26 @@ impl FilesystemLogSource {
 
 impl LogSource for FilesystemLogSource {
     fn into_stream(self) -> LogSourceStream {
-        let (tx, rx) = futures_mpsc::channel(10);
+        let (mut sender, rx) = BlockingSender::channel(self.buffer, self.overflow);
 
         thread::spawn(move || {
-            let mut tx = tx;
             let mut source = self;
 
             loop {
-                let record = source.next_record();
-                if let Err(error) = tx.try_send(record) {
-                    eprintln!("Channel error: {}", error);
+                let records: Vec<Result<LogRecord>> = match source.next_record() {
+                    Ok(record) => source
+                        .parser
+                        .parse_lines(record)
+                        .into_iter()
+                        .map(Ok)
+                        .collect(),
+                    Err(error) => vec![Err(error)],
+                };
+
+                for record in records {
+                    if sender.send(record).is_err() {
+                        return;
+                    }
                 }
             }
         });
@@ -142,6 +160,7 @@ mod tests {
         let config = FilesystemLogSourceConfig {
             delay: 100,
             entries: vec![dir_path.clone(), file_a_path.clone(), file_b_path.clone()],
+            ..Default::default()
         };
 
         let source = FilesystemLogSource::new(config).unwrap();
@@ -157,7 +176,7 @@ mod tests {
         let expected: Vec<LogRecord> = vec![
             LogRecord::new(&file_a_path_string, "file_a addition"),
             LogRecord::new(&file_b_path_string, "file_b addition"),
-            LogRecord::new(&format!("{} was created", file_c_path_string), ""),
+            LogRecord::new(format!("{} was created", file_c_path_string), ""),
             LogRecord::new(&file_c_path_string, "file_c addition"),
         ];
 
@@ -0,0 +1,101 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+pub const MAGIC: [u8; 2] = [0x1e, 0x0f];
+
+const HEADER_SIZE: usize = 12;
+const MAX_CHUNKS: u8 = 128;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Pending {
+    chunks: Vec<Option<Vec<u8>>>,
+    received: usize,
+    started: Instant,
+}
+
+/// Reassembles chunked GELF messages, incomplete messages are dropped after 5 seconds
+#[derive(Default)]
+pub struct Chunks {
+    pending: HashMap<[u8; 8], Pending>,
+}
+
+impl Chunks {
+    pub fn is_chunk(datagram: &[u8]) -> bool {
+        datagram.starts_with(&MAGIC)
+    }
+    /// Adds the chunk, returns the whole message when all its chunks are received
+    pub fn add(&mut self, datagram: &[u8], now: Instant) -> Option<Vec<u8>> {
+        self.pending
+            .retain(|_, pending| now.duration_since(pending.started) < TIMEOUT);
+
+        if datagram.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let mut id = [0; 8];
+        id.copy_from_slice(&datagram[2..10]);
+        let (number, count) = (datagram[10], datagram[11]);
+        if count == 0 || count > MAX_CHUNKS || number >= count {
+            return None;
+        }
+
+        let pending = self.pending.entry(id).or_insert_with(|| Pending {
+            chunks: vec![None; count as usize],
+            received: 0,
+            started: now,
+        });
+        let chunk = pending.chunks.get_mut(number as usize)?;
+        if chunk.is_none() {
+            *chunk = Some(datagram[HEADER_SIZE..].to_vec());
+            pending.received += 1;
+        }
+        if pending.received < pending.chunks.len() {
+            return None;
+        }
+
+        let pending = self.pending.remove(&id)?;
+        let message = pending.chunks.into_iter().flatten().flatten().collect();
+
+        Some(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Chunks, MAGIC};
+
+    fn chunk(id: u8, number: u8, count: u8, payload: &[u8]) -> Vec<u8> {
+        let mut chunk = MAGIC.to_vec();
+        chunk.extend_from_slice(&[id; 8]);
+        chunk.extend_from_slice(&[number, count]);
+        chunk.extend_from_slice(payload);
+
+        chunk
+    }
+
+    #[test]
+    fn main() {
+        let mut chunks = Chunks::default();
+        let now = Instant::now();
+
+        assert!(Chunks::is_chunk(&chunk(1, 0, 2, b"")));
+        assert_eq!(chunks.add(&chunk(1, 1, 3, b"dd"), now), None);
+        assert_eq!(chunks.add(&chunk(2, 0, 2, b"xx"), now), None);
+        assert_eq!(chunks.add(&chunk(1, 0, 3, b"aa"), now), None);
+        assert_eq!(chunks.add(&chunk(1, 0, 3, b"aa"), now), None);
+        assert_eq!(
+            chunks.add(&chunk(1, 2, 3, b"ee"), now),
+            Some(b"aaddee".to_vec())
+        );
+
+        // the first chunk of the message 2 has expired
+        let later = now + Duration::from_secs(6);
+        assert_eq!(chunks.add(&chunk(2, 1, 2, b"yy"), later), None);
+        assert_eq!(chunks.add(&chunk(3, 5, 2, b"zz"), later), None);
+        assert_eq!(chunks.pending.len(), 1);
+    }
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct GelfLogSourceConfig {
+    pub udp: Vec<SocketAddr>,
+    pub tcp: Vec<SocketAddr>,
+}
+
+impl Default for GelfLogSourceConfig {
+    fn default() -> Self {
+        GelfLogSourceConfig {
+            udp: vec![SocketAddr::from(([0, 0, 0, 0], 12201))],
+            tcp: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use anyhow::{anyhow, Result};
+use chrono::{TimeZone, Utc};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use serde_json::Value;
+use std::io::Read;
+
+use crate::source::{Level, LogRecord};
+
+const MAX_MESSAGE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Decompresses the message if it's compressed with zlib or gzip
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut message = Vec::new();
+
+    match bytes {
+        [0x1f, 0x8b, ..] => {
+            GzDecoder::new(bytes)
+                .take(MAX_MESSAGE_SIZE)
+                .read_to_end(&mut message)?;
+        }
+        // zlib headers for the default window size and each compression level
+        [0x78, 0x01, ..] | [0x78, 0x5e, ..] | [0x78, 0x9c, ..] | [0x78, 0xda, ..] => {
+            ZlibDecoder::new(bytes)
+                .take(MAX_MESSAGE_SIZE)
+                .read_to_end(&mut message)?;
+        }
+        _ => message.extend_from_slice(bytes),
+    }
+
+    Ok(message)
+}
+
+pub fn parse(bytes: &[u8]) -> Result<LogRecord> {
+    let fields = match serde_json::from_slice(bytes)? {
+        Value::Object(fields) => fields,
+        _ => return Err(anyhow!("GELF message is not a JSON object")),
+    };
+
+    let get_str = |key: &str| fields.get(key).and_then(Value::as_str);
+    let short_message =
+        get_str("short_message").ok_or_else(|| anyhow!("GELF message has no short_message"))?;
+    let host = get_str("host").unwrap_or("gelf");
+
+    let title = match get_str("_container_name") {
+        Some(container_name) => format!("{}@{}", container_name, host),
+        None => host.to_owned(),
+    };
+    let body = get_str("full_message").unwrap_or(short_message);
+    let mut record = LogRecord::new(title, body).with_metadata("host", host);
+
+    let level = fields
+        .get("level")
+        .and_then(Value::as_u64)
+        .and_then(|level| Level::from_syslog(level as u8));
+    if let Some(level) = level {
+        record = record.with_level(level);
+    }
+
+    let timestamp = fields
+        .get("timestamp")
+        .and_then(Value::as_f64)
+        .and_then(|seconds| Utc.timestamp_millis_opt((seconds * 1000.0) as i64).single());
+    if let Some(timestamp) = timestamp {
+        record = record.with_timestamp(timestamp);
+    }
+
+    for (key, value) in &fields {
+        // "_id" is reserved by the specification
+        let key = match key.strip_prefix('_') {
+            Some("id") | None => continue,
+            Some(key) => key,
+        };
+        let value = match value {
+            Value::String(value) => value.clone(),
+            Value::Null => continue,
+            value => value.to_string(),
+        };
+
+        record = record.with_metadata(key, value);
+    }
+
+    Ok(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use flate2::{
+        write::{GzEncoder, ZlibEncoder},
+        Compression,
+    };
+    use std::io::Write;
+
+    use crate::source::{Level, LogRecord};
+
+    use super::{decompress, parse};
+
+    static MESSAGE: &str = r#"{
+        "version": "1.1",
+        "host": "web-1",
+        "short_message": "connection refused",
+        "full_message": "connection refused\n  at db.connect",
+        "timestamp": 1609556645.5,
+        "level": 3,
+        "_container_name": "api",
+        "_image_name": "shop/api:1.2",
+        "_retries": 3,
+        "_id": "ignored"
+    }"#;
+
+    #[test]
+    fn main() {
+        let expected = LogRecord::new("api@web-1", "connection refused\n  at db.connect")
+            .with_level(Level::Error)
+            .with_timestamp(Utc.timestamp_millis(1_609_556_645_500))
+            .with_metadata("host", "web-1")
+            .with_metadata("container_name", "api")
+            .with_metadata("image_name", "shop/api:1.2")
+            .with_metadata("retries", "3");
+
+        assert_eq!(parse(MESSAGE.as_bytes()).unwrap(), expected);
+
+        let minimal = br#"{"version":"1.1","host":"db","short_message":"started"}"#;
+        let expected = LogRecord::new("db", "started").with_metadata("host", "db");
+        assert_eq!(parse(minimal).unwrap(), expected);
+
+        assert!(parse(br#"{"host":"db"}"#).is_err());
+        assert!(parse(b"not json").is_err());
+    }
+
+    #[test]
+    fn compression() {
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(MESSAGE.as_bytes()).unwrap();
+        let zlib = zlib.finish().unwrap();
+
+        let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+        gzip.write_all(MESSAGE.as_bytes()).unwrap();
+        let gzip = gzip.finish().unwrap();
+
+        assert_eq!(decompress(&zlib).unwrap(), MESSAGE.as_bytes());
+        assert_eq!(decompress(&gzip).unwrap(), MESSAGE.as_bytes());
+        assert_eq!(decompress(MESSAGE.as_bytes()).unwrap(), MESSAGE.as_bytes());
+    }
+}
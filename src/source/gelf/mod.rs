@@ -0,0 +1,207 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::{net, time::Instant};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+    net::{TcpListener, TcpStream, UdpSocket},
+};
+
+use crate::source::{LogRecord, LogSource, LogSourceStream};
+
+mod chunks;
+mod config;
+mod message;
+use self::chunks::Chunks;
+pub use self::config::GelfLogSourceConfig;
+
+type RecordSender = Sender<Result<LogRecord>>;
+
+const MAX_DATAGRAM_SIZE: usize = 64 * 1024;
+const MAX_FRAME_SIZE: usize = 1024 * 1024;
+
+pub struct GelfLogSource {
+    udp: Vec<UdpSocket>,
+    tcp: Vec<TcpListener>,
+}
+
+impl GelfLogSource {
+    pub fn new(config: GelfLogSourceConfig) -> Result<Self> {
+        let mut udp = Vec::new();
+        for addr in config.udp {
+            let socket = net::UdpSocket::bind(addr)?;
+            udp.push(UdpSocket::from_std(socket)?);
+        }
+
+        let mut tcp = Vec::new();
+        for addr in config.tcp {
+            let listener = net::TcpListener::bind(addr)?;
+            tcp.push(TcpListener::from_std(listener)?);
+        }
+
+        Ok(GelfLogSource { udp, tcp })
+    }
+}
+
+impl LogSource for GelfLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        for socket in self.udp {
+            tokio::spawn(listen_udp(socket, tx.clone()));
+        }
+        for listener in self.tcp {
+            tokio::spawn(listen_tcp(listener, tx.clone()));
+        }
+
+        Box::pin(rx)
+    }
+}
+
+fn datagram_to_record(datagram: &[u8]) -> Result<LogRecord> {
+    let message = message::decompress(datagram)?;
+
+    message::parse(&message)
+}
+
+async fn listen_udp(mut socket: UdpSocket, mut tx: RecordSender) {
+    let mut buffer = vec![0; MAX_DATAGRAM_SIZE];
+    let mut chunks = Chunks::default();
+
+    loop {
+        let result = match socket.recv(&mut buffer).await {
+            Ok(len) if Chunks::is_chunk(&buffer[..len]) => {
+                match chunks.add(&buffer[..len], Instant::now()) {
+                    Some(message) => datagram_to_record(&message),
+                    None => continue,
+                }
+            }
+            Ok(len) => datagram_to_record(&buffer[..len]),
+            Err(error) => Err(error.into()),
+        };
+
+        if tx.send(result).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn listen_tcp(mut listener: TcpListener, mut tx: RecordSender) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let mut tx = tx.clone();
+
+                tokio::spawn(async move {
+                    if let Err(error) = read_tcp(stream, tx.clone()).await {
+                        let error = error.context(format!("GELF connection from {}", peer.ip()));
+                        let _ = tx.send(Err(error)).await;
+                    }
+                });
+            }
+            Err(error) => {
+                if tx.send(Err(error.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Reads the null byte delimited messages, compression is not supported over TCP
+async fn read_tcp(stream: TcpStream, mut tx: RecordSender) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let mut frame = Vec::new();
+        let mut limited = (&mut reader).take(MAX_FRAME_SIZE as u64 + 1);
+        if limited.read_until(b'\0', &mut frame).await? == 0 {
+            return Ok(());
+        }
+        if frame.last() == Some(&b'\0') {
+            frame.pop();
+        } else if frame.len() > MAX_FRAME_SIZE {
+            return Err(anyhow!(
+                "GELF message is larger than {} bytes",
+                MAX_FRAME_SIZE
+            ));
+        }
+        if frame.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        if tx.send(message::parse(&frame)).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::{write::ZlibEncoder, Compression};
+    use futures::StreamExt;
+    use std::{
+        io::Write,
+        net::{TcpStream, UdpSocket},
+    };
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{chunks::MAGIC, GelfLogSource, GelfLogSourceConfig, MAX_FRAME_SIZE};
+
+    #[tokio::test]
+    async fn main() {
+        let config = GelfLogSourceConfig {
+            udp: vec!["127.0.0.1:12211".parse().unwrap()],
+            tcp: vec!["127.0.0.1:12211".parse().unwrap()],
+        };
+
+        let source = GelfLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let message = br#"{"version":"1.1","host":"web","short_message":"chunked","level":4}"#;
+        let mut zlib = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib.write_all(message).unwrap();
+        let compressed = zlib.finish().unwrap();
+
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let parts: Vec<&[u8]> = compressed.chunks(compressed.len() / 2 + 1).collect();
+        for (number, part) in parts.iter().enumerate().rev() {
+            let mut chunk = MAGIC.to_vec();
+            chunk.extend_from_slice(&[7; 8]);
+            chunk.extend_from_slice(&[number as u8, parts.len() as u8]);
+            chunk.extend_from_slice(part);
+            udp.send_to(&chunk, "127.0.0.1:12211").unwrap();
+        }
+
+        let record = stream.next().await.unwrap().unwrap();
+        let expected = LogRecord::new("web", "chunked")
+            .with_level(Level::Warning)
+            .with_metadata("host", "web");
+        assert_eq!(record, expected);
+
+        let mut tcp = TcpStream::connect("127.0.0.1:12211").unwrap();
+        tcp.write_all(b"{\"host\":\"db\",\"short_message\":\"first\"}\0")
+            .unwrap();
+        tcp.write_all(b"{\"host\":\"db\",\"short_message\":\"second\",\"_pid\":42}\0")
+            .unwrap();
+
+        let actual: Vec<LogRecord> = (&mut stream).take(2).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("db", "first").with_metadata("host", "db"),
+            LogRecord::new("db", "second")
+                .with_metadata("host", "db")
+                .with_metadata("pid", "42"),
+        ];
+        assert_eq!(actual, expected);
+
+        tcp.write_all(&vec![b' '; MAX_FRAME_SIZE + 1]).unwrap();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "GELF connection from 127.0.0.1: GELF message is larger than 1048576 bytes"
+        );
+    }
+}
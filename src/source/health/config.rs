@@ -0,0 +1,39 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Target {
+    pub title: Option<String>,
+    /// `host:port` to connect to
+    pub tcp: Option<String>,
+    /// URL to send a GET request to
+    pub http: Option<String>,
+    /// Expected HTTP status, any 2xx status by default
+    pub status: Option<u16>,
+    /// Substring expected in the HTTP response body
+    pub contains: Option<String>,
+    /// Timeout in milliseconds
+    #[serde(default = "Target::default_timeout")]
+    pub timeout: u64,
+}
+
+impl Target {
+    fn default_timeout() -> u64 {
+        5000
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HealthLogSourceConfig {
+    pub interval: u64,
+    pub targets: Vec<Target>,
+}
+
+impl Default for HealthLogSourceConfig {
+    fn default() -> Self {
+        HealthLogSourceConfig {
+            interval: 30,
+            targets: Vec::new(),
+        }
+    }
+}
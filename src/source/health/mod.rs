@@ -0,0 +1,163 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    future, SinkExt,
+};
+use std::time::Duration;
+use tokio::time::delay_for;
+
+use crate::source::{Level, LogRecord, LogSource, LogSourceStream};
+
+mod config;
+mod probe;
+pub use self::config::HealthLogSourceConfig;
+use self::probe::{Outcome, Probe};
+
+struct Check {
+    title: String,
+    probe: Probe,
+    timeout: Duration,
+    up: bool,
+}
+
+impl Check {
+    /// Returns a record when the state of the target changes,
+    /// targets are considered up until the first failed check
+    fn update(&mut self, outcome: Outcome) -> Option<LogRecord> {
+        let latency = outcome.latency.as_millis().to_string();
+
+        match (self.up, outcome.result) {
+            (true, Err(error)) => {
+                self.up = false;
+
+                let body = format!("{}: {}", self.probe.address(), error);
+                let record = LogRecord::new(format!("{} is DOWN", self.title), body)
+                    .with_level(Level::Error)
+                    .with_metadata("target", self.probe.address())
+                    .with_metadata("latency_ms", latency)
+                    .with_metadata("error", error.to_string())
+                    .with_thread(self.title.clone(), false);
+
+                Some(record)
+            }
+            (false, Ok(())) => {
+                self.up = true;
+
+                let body = format!("{} responded in {} ms", self.probe.address(), latency);
+                let record = LogRecord::new(format!("{} RECOVERED", self.title), body)
+                    .with_level(Level::Info)
+                    .with_metadata("target", self.probe.address())
+                    .with_metadata("latency_ms", latency)
+                    .with_thread(self.title.clone(), true);
+
+                Some(record)
+            }
+            _ => None,
+        }
+    }
+}
+
+pub struct HealthLogSource {
+    interval: Duration,
+    checks: Vec<Check>,
+}
+
+impl HealthLogSource {
+    pub fn new(config: HealthLogSourceConfig) -> Result<Self> {
+        let mut checks = Vec::new();
+        for target in config.targets {
+            let probe = Probe::new(&target)?;
+            let title = target.title.unwrap_or_else(|| probe.address().to_owned());
+
+            checks.push(Check {
+                title,
+                probe,
+                timeout: Duration::from_millis(target.timeout),
+                up: true,
+            });
+        }
+
+        Ok(HealthLogSource {
+            interval: Duration::from_secs(config.interval),
+            checks,
+        })
+    }
+}
+
+impl LogSource for HealthLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+async fn run(mut source: HealthLogSource, mut tx: Sender<Result<LogRecord>>) {
+    loop {
+        let runs = source
+            .checks
+            .iter()
+            .map(|check| check.probe.run(check.timeout));
+        let outcomes = future::join_all(runs).await;
+
+        for (check, outcome) in source.checks.iter_mut().zip(outcomes) {
+            if let Some(record) = check.update(outcome) {
+                if tx.send(Ok(record)).await.is_err() {
+                    return;
+                }
+            }
+        }
+
+        delay_for(source.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::net::TcpListener;
+
+    use crate::source::{Level, LogSource};
+
+    use super::{config::Target, HealthLogSource, HealthLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let config = HealthLogSourceConfig {
+            interval: 1,
+            targets: vec![Target {
+                title: Some(String::from("Database")),
+                tcp: Some(addr.clone()),
+                http: None,
+                status: None,
+                contains: None,
+                timeout: 1000,
+            }],
+        };
+
+        let source = HealthLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, "Database is DOWN");
+        assert_eq!(record.level, Some(Level::Error));
+        assert!(record.body.starts_with(&format!("{}: ", addr)));
+        assert_eq!(record.metadata["target"], addr);
+        assert!(record.metadata.contains_key("error"));
+        assert!(!record.thread.unwrap().close);
+
+        let _listener = TcpListener::bind(&addr).unwrap();
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, "Database RECOVERED");
+        assert_eq!(record.level, Some(Level::Info));
+        assert!(record.metadata.contains_key("latency_ms"));
+        assert!(record.thread.unwrap().close);
+    }
+}
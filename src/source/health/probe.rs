@@ -0,0 +1,189 @@
+use anyhow::{anyhow, Result};
+use reqwest::{Client, StatusCode};
+use std::time::{Duration, Instant};
+use tokio::{net::TcpStream, time};
+
+use super::config::Target;
+
+#[derive(Debug)]
+pub enum Probe {
+    Tcp {
+        addr: String,
+    },
+    Http {
+        client: Client,
+        url: String,
+        status: Option<StatusCode>,
+        contains: Option<String>,
+    },
+}
+
+/// Outcome of a single check along with the time it took
+#[derive(Debug)]
+pub struct Outcome {
+    pub latency: Duration,
+    pub result: Result<()>,
+}
+
+impl Probe {
+    pub fn new(target: &Target) -> Result<Self> {
+        let timeout = Duration::from_millis(target.timeout);
+
+        match (&target.tcp, &target.http) {
+            (Some(addr), None) => Ok(Probe::Tcp { addr: addr.clone() }),
+            (None, Some(url)) => {
+                let status = match target.status {
+                    Some(status) => Some(StatusCode::from_u16(status)?),
+                    None => None,
+                };
+
+                Ok(Probe::Http {
+                    client: Client::builder().timeout(timeout).build()?,
+                    url: url.clone(),
+                    status,
+                    contains: target.contains.clone(),
+                })
+            }
+            _ => Err(anyhow!("Health target requires either tcp or http")),
+        }
+    }
+    pub fn address(&self) -> &str {
+        match self {
+            Probe::Tcp { addr } => addr,
+            Probe::Http { url, .. } => url,
+        }
+    }
+    pub async fn run(&self, timeout: Duration) -> Outcome {
+        let start = Instant::now();
+        let result = match time::timeout(timeout, self.check()).await {
+            Ok(result) => result,
+            Err(_) => Err(anyhow!("Timed out after {} ms", timeout.as_millis())),
+        };
+
+        Outcome {
+            latency: start.elapsed(),
+            result,
+        }
+    }
+    async fn check(&self) -> Result<()> {
+        match self {
+            Probe::Tcp { addr } => {
+                TcpStream::connect(addr.as_str()).await?;
+                Ok(())
+            }
+            Probe::Http {
+                client,
+                url,
+                status,
+                contains,
+            } => {
+                let resp = client.get(url).send().await?;
+
+                let actual = resp.status();
+                match status {
+                    Some(expected) if actual != *expected => {
+                        return Err(anyhow!(
+                            "Unexpected status {}, expected {}",
+                            actual,
+                            expected
+                        ))
+                    }
+                    None if !actual.is_success() => {
+                        return Err(anyhow!("Unexpected status {}", actual))
+                    }
+                    _ => {}
+                }
+
+                if let Some(contains) = contains {
+                    let body = resp.text().await?;
+                    if !body.contains(contains.as_str()) {
+                        return Err(anyhow!("Response doesn't contain \"{}\"", contains));
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    use super::{super::config::Target, Probe};
+
+    fn serve(response: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut buffer = [0; 1024];
+                let _ = stream.read(&mut buffer).unwrap();
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        format!("http://{}/health", addr)
+    }
+
+    fn target(url: String) -> Target {
+        Target {
+            title: None,
+            tcp: None,
+            http: Some(url),
+            status: None,
+            contains: None,
+            timeout: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn http() {
+        let timeout = Duration::from_secs(1);
+        let url = serve("HTTP/1.1 200 OK\r\nContent-Length: 6\r\nConnection: close\r\n\r\nstatus");
+
+        let probe = Probe::new(&target(url.clone())).unwrap();
+        assert!(probe.run(timeout).await.result.is_ok());
+
+        let probe = Probe::new(&Target {
+            contains: Some(String::from("ok")),
+            ..target(url.clone())
+        })
+        .unwrap();
+        let error = probe.run(timeout).await.result.unwrap_err();
+        assert_eq!(error.to_string(), "Response doesn't contain \"ok\"");
+
+        let probe = Probe::new(&Target {
+            status: Some(204),
+            ..target(url)
+        })
+        .unwrap();
+        let error = probe.run(timeout).await.result.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected status 200 OK, expected 204 No Content"
+        );
+
+        let url = serve("HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\n\r\n");
+        let probe = Probe::new(&target(url)).unwrap();
+        let error = probe.run(timeout).await.result.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unexpected status 503 Service Unavailable"
+        );
+
+        let invalid = Target {
+            tcp: Some(String::from("127.0.0.1:80")),
+            ..target(String::from("http://127.0.0.1/"))
+        };
+        assert!(Probe::new(&invalid).is_err());
+    }
+}
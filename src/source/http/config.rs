@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Endpoint {
+    pub path: String,
+    pub token: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct HttpLogSourceConfig {
+    pub addr: SocketAddr,
+    pub endpoints: Vec<Endpoint>,
+    pub max_body: usize,
+}
+
+impl Default for HttpLogSourceConfig {
+    fn default() -> Self {
+        HttpLogSourceConfig {
+            addr: SocketAddr::from(([127, 0, 0, 1], 8080)),
+            endpoints: vec![Endpoint {
+                path: String::from("/ingest"),
+                token: None,
+                title: None,
+            }],
+            max_body: 1024 * 1024,
+        }
+    }
+}
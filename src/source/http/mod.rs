@@ -0,0 +1,174 @@
+use anyhow::Result;
+use hyper::{
+    header::CONTENT_TYPE, server::conn::AddrIncoming, server::Builder, Body, Request, Server,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::source::{
+    webhook::{self, Rejection},
+    Level, LogRecord, LogSource, LogSourceStream,
+};
+
+mod config;
+pub use self::config::{Endpoint, HttpLogSourceConfig};
+
+#[derive(Debug, Deserialize)]
+struct IngestMessage {
+    title: Option<String>,
+    #[serde(default)]
+    body: String,
+    level: Option<String>,
+}
+
+pub struct HttpLogSource {
+    builder: Builder<AddrIncoming>,
+    endpoints: Arc<Vec<Endpoint>>,
+    max_body: usize,
+}
+
+impl HttpLogSource {
+    pub fn new(config: HttpLogSourceConfig) -> Result<Self> {
+        let builder = Server::try_bind(&config.addr)?;
+
+        Ok(HttpLogSource {
+            builder,
+            endpoints: Arc::new(config.endpoints),
+            max_body: config.max_body,
+        })
+    }
+}
+
+impl LogSource for HttpLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let endpoints = self.endpoints;
+        let max_body = self.max_body;
+
+        webhook::serve(self.builder, move |req| {
+            parse_request(endpoints.clone(), max_body, req)
+        })
+    }
+}
+
+async fn parse_request(
+    endpoints: Arc<Vec<Endpoint>>,
+    max_body: usize,
+    req: Request<Body>,
+) -> Result<Vec<LogRecord>, Rejection> {
+    let endpoint = endpoints
+        .iter()
+        .find(|endpoint| endpoint.path == req.uri().path())
+        .ok_or_else(webhook::not_found)?;
+    webhook::check_request(&req, endpoint.token.as_deref())?;
+
+    let is_json = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    let bytes = webhook::read_body(req, max_body).await?;
+
+    let title = endpoint
+        .title
+        .clone()
+        .unwrap_or_else(|| endpoint.path.clone());
+    if !is_json {
+        return Ok(vec![LogRecord::new(title, String::from_utf8_lossy(&bytes))]);
+    }
+
+    let message: IngestMessage = serde_json::from_slice(&bytes).map_err(webhook::bad_request)?;
+    let mut record = LogRecord::new(message.title.unwrap_or(title), message.body);
+
+    if let Some(level) = message.level {
+        let level: Level = level.parse().map_err(webhook::bad_request)?;
+        record = record.with_level(level);
+    }
+
+    Ok(vec![record])
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use reqwest::{Client, StatusCode};
+    use serde_json::json;
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{Endpoint, HttpLogSource, HttpLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let config = HttpLogSourceConfig {
+            addr: "127.0.0.1:18080".parse().unwrap(),
+            endpoints: vec![
+                Endpoint {
+                    path: String::from("/ingest"),
+                    token: Some(String::from("secret")),
+                    title: None,
+                },
+                Endpoint {
+                    path: String::from("/backup"),
+                    token: None,
+                    title: Some(String::from("Backup")),
+                },
+            ],
+            max_body: 64,
+        };
+
+        let source = HttpLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+        let client = Client::new();
+
+        let json = json!({"title": "CI", "body": "build failed", "level": "error"});
+        let status = |resp: reqwest::Response| resp.status();
+
+        let response = client
+            .post("http://127.0.0.1:18080/ingest")
+            .json(&json)
+            .send()
+            .await
+            .map(status);
+        assert_eq!(response.unwrap(), StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .post("http://127.0.0.1:18080/ingest")
+            .bearer_auth("secret")
+            .json(&json)
+            .send()
+            .await
+            .map(status);
+        assert_eq!(response.unwrap(), StatusCode::ACCEPTED);
+
+        let response = client
+            .post("http://127.0.0.1:18080/backup")
+            .body("backup done")
+            .send()
+            .await
+            .map(status);
+        assert_eq!(response.unwrap(), StatusCode::ACCEPTED);
+
+        let response = client
+            .get("http://127.0.0.1:18080/backup")
+            .send()
+            .await
+            .map(status);
+        assert_eq!(response.unwrap(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let response = client
+            .post("http://127.0.0.1:18080/backup")
+            .body("x".repeat(65))
+            .send()
+            .await
+            .map(status);
+        assert_eq!(response.unwrap(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("CI", "build failed").with_level(Level::Error),
+            LogRecord::new("Backup", "backup done"),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
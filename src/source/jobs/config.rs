@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use crate::source::OverflowPolicy;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Schedule {
+    /// Substring of the cron command or the unit name
+    pub job: String,
+    /// Maximal number of seconds between two runs
+    pub every: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct JobsLogSourceConfig {
+    pub cron: bool,
+    pub units: Vec<String>,
+    pub successes: bool,
+    pub output_lines: usize,
+    pub schedules: Vec<Schedule>,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for JobsLogSourceConfig {
+    fn default() -> Self {
+        JobsLogSourceConfig {
+            cron: true,
+            units: Vec::new(),
+            successes: false,
+            output_lines: 20,
+            schedules: Vec::new(),
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+}
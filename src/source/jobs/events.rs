@@ -0,0 +1,232 @@
+use regex::Regex;
+use std::collections::BTreeMap;
+
+const MESSAGE_JOB_START: &str = "7d4958e842da4a758f6c1cdc7b36dcc5";
+const MESSAGE_UNIT_PROCESS_EXIT: &str = "98e322203f7a4ed290d09fe03c09fe15";
+const MESSAGE_UNIT_SUCCESS: &str = "7ad2d189f7e94e70a38c781354912448";
+const MESSAGE_UNIT_FAILURE_RESULT: &str = "d9b373ed55a64feb8242e02dbe79a49c";
+
+pub const CRON_IDENTIFIERS: &[&str] = &["CRON", "crond"];
+
+#[derive(Debug, PartialEq)]
+pub enum JobEvent {
+    CronStart {
+        pid: String,
+        user: String,
+        command: String,
+    },
+    CronOutput {
+        pid: String,
+        line: String,
+    },
+    CronFailed {
+        pid: String,
+        status: String,
+    },
+    CronEnd {
+        pid: String,
+    },
+    UnitStart {
+        unit: String,
+    },
+    UnitOutput {
+        unit: String,
+        line: String,
+    },
+    UnitExit {
+        unit: String,
+        status: String,
+    },
+    /// `result` is "success" or the `UNIT_RESULT` of a failed unit, e.g. "exit-code"
+    UnitResult {
+        unit: String,
+        result: String,
+    },
+}
+
+impl JobEvent {
+    /// Name of the job if the event is a start of a run
+    pub fn started_job(&self) -> Option<&str> {
+        match self {
+            JobEvent::CronStart { command, .. } => Some(command),
+            JobEvent::UnitStart { unit } => Some(unit),
+            _ => None,
+        }
+    }
+}
+
+/// Turns journal entries of cron and systemd into job events
+pub struct EventParser {
+    cron: Regex,
+    cron_failed: Regex,
+    units: Vec<String>,
+}
+
+impl EventParser {
+    pub fn new(units: Vec<String>) -> Self {
+        EventParser {
+            cron: Regex::new(
+                r"^\((?P<user>[^)]+)\) (?P<kind>CMD|CMDOUT|CMDEND|END) \((?P<text>.*)\)$",
+            )
+            .unwrap(),
+            cron_failed: Regex::new(r"grandchild #\d+ failed with exit status (?P<status>\d+)")
+                .unwrap(),
+            units,
+        }
+    }
+    pub fn parse(&self, fields: &BTreeMap<String, String>) -> Option<JobEvent> {
+        let get = |key: &str| fields.get(key).map(String::as_str);
+        let message = get("MESSAGE")?;
+
+        let identifier = get("SYSLOG_IDENTIFIER").unwrap_or_default();
+        if CRON_IDENTIFIERS.contains(&identifier) {
+            return self.parse_cron(get("_PID")?.to_owned(), message);
+        }
+
+        // messages of systemd about the unit
+        if let Some(unit) = get("UNIT").filter(|unit| self.is_watched(unit)) {
+            let unit = unit.to_owned();
+
+            return match get("MESSAGE_ID")? {
+                MESSAGE_JOB_START => Some(JobEvent::UnitStart { unit }),
+                MESSAGE_UNIT_PROCESS_EXIT => {
+                    let status = format!("{}, status={}", get("EXIT_CODE")?, get("EXIT_STATUS")?);
+                    Some(JobEvent::UnitExit { unit, status })
+                }
+                MESSAGE_UNIT_SUCCESS => Some(JobEvent::UnitResult {
+                    unit,
+                    result: String::from("success"),
+                }),
+                MESSAGE_UNIT_FAILURE_RESULT => Some(JobEvent::UnitResult {
+                    unit,
+                    result: get("UNIT_RESULT")?.to_owned(),
+                }),
+                _ => None,
+            };
+        }
+
+        // output of the unit itself
+        let unit = get("_SYSTEMD_UNIT").filter(|unit| self.is_watched(unit))?;
+        Some(JobEvent::UnitOutput {
+            unit: unit.to_owned(),
+            line: message.to_owned(),
+        })
+    }
+    fn parse_cron(&self, pid: String, message: &str) -> Option<JobEvent> {
+        if let Some(captures) = self.cron_failed.captures(message) {
+            let status = captures["status"].to_owned();
+            return Some(JobEvent::CronFailed { pid, status });
+        }
+
+        let captures = self.cron.captures(message)?;
+        let text = captures["text"].to_owned();
+
+        match &captures["kind"] {
+            "CMD" => Some(JobEvent::CronStart {
+                pid,
+                user: captures["user"].to_owned(),
+                command: text,
+            }),
+            "CMDOUT" => Some(JobEvent::CronOutput { pid, line: text }),
+            _ => Some(JobEvent::CronEnd { pid }),
+        }
+    }
+    fn is_watched(&self, unit: &str) -> bool {
+        self.units.iter().any(|watched| watched == unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{EventParser, JobEvent};
+
+    fn fields(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn main() {
+        let parser = EventParser::new(vec![String::from("backup.service")]);
+
+        let entry = fields(&[
+            ("SYSLOG_IDENTIFIER", "CRON"),
+            ("_PID", "42"),
+            (
+                "MESSAGE",
+                "(root) CMD (/usr/local/bin/rotate.sh >/dev/null)",
+            ),
+        ]);
+        let expected = JobEvent::CronStart {
+            pid: String::from("42"),
+            user: String::from("root"),
+            command: String::from("/usr/local/bin/rotate.sh >/dev/null"),
+        };
+        assert_eq!(parser.parse(&entry), Some(expected));
+
+        let entry = fields(&[
+            ("SYSLOG_IDENTIFIER", "CRON"),
+            ("_PID", "42"),
+            (
+                "MESSAGE",
+                "(CRON) error (grandchild #43 failed with exit status 2)",
+            ),
+        ]);
+        let expected = JobEvent::CronFailed {
+            pid: String::from("42"),
+            status: String::from("2"),
+        };
+        assert_eq!(parser.parse(&entry), Some(expected));
+
+        let entry = fields(&[
+            ("SYSLOG_IDENTIFIER", "crond"),
+            ("_PID", "42"),
+            (
+                "MESSAGE",
+                "(root) CMDEND (/usr/local/bin/rotate.sh >/dev/null)",
+            ),
+        ]);
+        let expected = JobEvent::CronEnd {
+            pid: String::from("42"),
+        };
+        assert_eq!(parser.parse(&entry), Some(expected));
+
+        let entry = fields(&[
+            ("_PID", "1"),
+            ("UNIT", "backup.service"),
+            ("MESSAGE_ID", "98e322203f7a4ed290d09fe03c09fe15"),
+            ("EXIT_CODE", "exited"),
+            ("EXIT_STATUS", "1"),
+            (
+                "MESSAGE",
+                "backup.service: Main process exited, code=exited, status=1/FAILURE",
+            ),
+        ]);
+        let expected = JobEvent::UnitExit {
+            unit: String::from("backup.service"),
+            status: String::from("exited, status=1"),
+        };
+        assert_eq!(parser.parse(&entry), Some(expected));
+
+        let entry = fields(&[
+            ("_SYSTEMD_UNIT", "backup.service"),
+            ("MESSAGE", "rsync: connection refused"),
+        ]);
+        let expected = JobEvent::UnitOutput {
+            unit: String::from("backup.service"),
+            line: String::from("rsync: connection refused"),
+        };
+        assert_eq!(parser.parse(&entry), Some(expected));
+
+        let entry = fields(&[
+            ("UNIT", "other.service"),
+            ("MESSAGE_ID", "7ad2d189f7e94e70a38c781354912448"),
+            ("MESSAGE", "other.service: Deactivated successfully."),
+        ]);
+        assert_eq!(parser.parse(&entry), None);
+    }
+}
@@ -0,0 +1,299 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use futures::{
+    channel::oneshot::{self, Sender as OneshotSender},
+    executor,
+};
+use std::{thread, time::Duration};
+use systemd::journal::{Journal, JournalFiles, JournalSeek};
+
+use crate::source::{
+    BlockingReceiver, BlockingSender, Level, LogRecord, LogSource, LogSourceStream,
+};
+
+mod config;
+mod events;
+mod runs;
+pub use self::config::JobsLogSourceConfig;
+use self::{
+    config::Schedule,
+    events::{EventParser, CRON_IDENTIFIERS},
+    runs::{Outcome, Runs, Schedules},
+};
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+pub struct JobsLogSource {
+    receiver: BlockingReceiver,
+}
+
+impl JobsLogSource {
+    pub fn new(config: JobsLogSourceConfig) -> Result<Self> {
+        let (init_tx, init_rx) = oneshot::channel();
+        let (record_tx, record_rx) = BlockingSender::channel(config.buffer, config.overflow);
+
+        thread::spawn(move || run_inner(config, init_tx, record_tx));
+
+        match executor::block_on(init_rx).unwrap() {
+            Err(error) => Err(error),
+            Ok(_) => Ok(JobsLogSource {
+                receiver: record_rx,
+            }),
+        }
+    }
+}
+
+impl LogSource for JobsLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        Box::pin(self.receiver)
+    }
+}
+
+struct JobsLogSourceInner {
+    journal: Journal,
+    parser: EventParser,
+    runs: Runs,
+    schedules: Schedules,
+    successes: bool,
+}
+
+impl JobsLogSourceInner {
+    fn new(config: JobsLogSourceConfig) -> Result<Self> {
+        if !config.cron && config.units.is_empty() {
+            return Err(anyhow!("Jobs log source requires cron or units to follow"));
+        }
+
+        let mut journal = Journal::open(JournalFiles::All, false, true)?;
+
+        let mut groups: Vec<Vec<(&str, &str)>> = Vec::new();
+        if config.cron {
+            let group = CRON_IDENTIFIERS
+                .iter()
+                .map(|identifier| ("SYSLOG_IDENTIFIER", *identifier))
+                .collect();
+            groups.push(group);
+        }
+        if !config.units.is_empty() {
+            for field in &["UNIT", "_SYSTEMD_UNIT"] {
+                let group = config
+                    .units
+                    .iter()
+                    .map(|unit| (*field, unit.as_str()))
+                    .collect();
+                groups.push(group);
+            }
+        }
+
+        for (index, group) in groups.iter().enumerate() {
+            if index > 0 {
+                journal.match_or()?;
+            }
+            for (key, value) in group {
+                journal.match_add(key, *value)?;
+            }
+        }
+
+        journal.seek_tail()?;
+        journal.seek(JournalSeek::Tail)?;
+
+        Ok(JobsLogSourceInner {
+            journal,
+            parser: EventParser::new(config.units),
+            runs: Runs::new(config.output_lines, run_ttl(&config.schedules)),
+            schedules: Schedules::new(config.schedules, Utc::now()),
+            successes: config.successes,
+        })
+    }
+    fn next_records(&mut self) -> Result<Vec<LogRecord>> {
+        let mut records = Vec::new();
+        let mut has_entries = false;
+
+        while let Some(entry) = self.journal.next_entry()? {
+            has_entries = true;
+
+            let time = match self.journal.timestamp() {
+                Ok(time) => DateTime::from(time),
+                Err(_) => Utc::now(),
+            };
+            let event = match self.parser.parse(&entry) {
+                Some(event) => event,
+                None => continue,
+            };
+
+            if let Some(job) = event.started_job() {
+                self.schedules.started(job, time);
+            }
+            if let Some(outcome) = self.runs.handle(event, time) {
+                if outcome.success && !self.successes {
+                    continue;
+                }
+                records.push(outcome_to_record(outcome));
+            }
+        }
+
+        let missed = self.schedules.missed(Utc::now());
+        records.extend(
+            missed
+                .into_iter()
+                .map(|(schedule, last_run)| missed_to_record(schedule, last_run)),
+        );
+
+        if !has_entries {
+            self.journal.wait(Some(WAIT_TIMEOUT))?;
+        }
+
+        Ok(records)
+    }
+}
+
+fn outcome_to_record(outcome: Outcome) -> LogRecord {
+    let mut lines = Vec::new();
+    if let Some(status) = &outcome.status {
+        lines.push(format!("Status: {}", status));
+    }
+    if let Some(duration) = outcome.duration {
+        lines.push(format!("Duration: {}", format_duration(duration)));
+    }
+    if !outcome.output.is_empty() {
+        lines.push(format!("Output:\n{}", outcome.output.join("\n")));
+    }
+
+    let (title, level) = if outcome.success {
+        (format!("Job {} succeeded", outcome.job), Level::Info)
+    } else {
+        (format!("Job {} failed", outcome.job), Level::Error)
+    };
+
+    let mut record = LogRecord::new(title, lines.join("\n"))
+        .with_level(level)
+        .with_metadata("job", outcome.job);
+    if let Some(user) = outcome.user {
+        record = record.with_metadata("user", user);
+    }
+    if let Some(status) = outcome.status {
+        record = record.with_metadata("status", status);
+    }
+    if let Some(duration) = outcome.duration {
+        record = record.with_metadata("duration", duration.num_seconds().to_string());
+    }
+
+    record
+}
+
+fn missed_to_record(schedule: Schedule, last_run: DateTime<Utc>) -> LogRecord {
+    let every = ChronoDuration::seconds(schedule.every as i64);
+    let body = format!(
+        "No runs since {}, expected every {}",
+        last_run.format("%Y-%m-%d %H:%M:%S UTC"),
+        format_duration(every)
+    );
+
+    LogRecord::new(format!("Job {} did not run", schedule.job), body)
+        .with_level(Level::Error)
+        .with_metadata("job", schedule.job)
+}
+
+/// Cron runs without the end are forgotten after a day, or the longest schedule if it's longer
+fn run_ttl(schedules: &[Schedule]) -> ChronoDuration {
+    let longest = schedules.iter().map(|schedule| schedule.every).max();
+
+    ChronoDuration::seconds(longest.unwrap_or(0) as i64).max(ChronoDuration::days(1))
+}
+
+fn format_duration(duration: ChronoDuration) -> String {
+    let seconds = duration.num_seconds().max(0);
+    let (hours, minutes) = (seconds / 3600, seconds / 60 % 60);
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn run_inner(
+    config: JobsLogSourceConfig,
+    init_tx: OneshotSender<Result<()>>,
+    mut record_tx: BlockingSender,
+) {
+    let mut inner = match JobsLogSourceInner::new(config) {
+        Ok(inner) => {
+            init_tx.send(Ok(())).unwrap();
+            inner
+        }
+        Err(error) => {
+            init_tx.send(Err(error)).unwrap();
+            return;
+        }
+    };
+
+    loop {
+        let results = match inner.next_records() {
+            Ok(records) => records.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        };
+
+        for result in results {
+            if record_tx.send(result).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use futures::StreamExt;
+    use systemd::journal;
+
+    use crate::source::{Level, LogSource};
+
+    use super::{
+        format_duration, outcome_to_record, runs::Outcome, JobsLogSource, JobsLogSourceConfig,
+    };
+
+    #[test]
+    fn record() {
+        let outcome = Outcome {
+            job: String::from("/usr/local/bin/rotate.sh"),
+            user: Some(String::from("root")),
+            success: false,
+            status: Some(String::from("exit status 2")),
+            duration: Some(Duration::seconds(65)),
+            output: vec![String::from("disk full")],
+        };
+
+        let record = outcome_to_record(outcome);
+        assert_eq!(record.title, "Job /usr/local/bin/rotate.sh failed");
+        assert_eq!(
+            record.body,
+            "Status: exit status 2\nDuration: 1m 5s\nOutput:\ndisk full"
+        );
+        assert_eq!(record.level, Some(Level::Error));
+        assert_eq!(record.metadata["duration"], "65");
+
+        assert_eq!(format_duration(Duration::seconds(3725)), "1h 2m");
+    }
+
+    #[tokio::test]
+    async fn main() {
+        let source = JobsLogSource::new(JobsLogSourceConfig::default()).unwrap();
+        let mut stream = source.into_stream();
+
+        journal::send(&[
+            "SYSLOG_IDENTIFIER=CRON",
+            "MESSAGE=(root) CMD (logram-test-job)",
+        ]);
+        journal::send(&[
+            "SYSLOG_IDENTIFIER=CRON",
+            "MESSAGE=(CRON) error (grandchild #2 failed with exit status 3)",
+        ]);
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, "Job logram-test-job failed");
+        assert_eq!(record.metadata["status"], "exit status 3");
+    }
+}
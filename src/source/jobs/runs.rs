@@ -0,0 +1,293 @@
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+
+use super::{config::Schedule, events::JobEvent};
+
+/// Result of a finished run
+#[derive(Debug, PartialEq)]
+pub struct Outcome {
+    pub job: String,
+    pub user: Option<String>,
+    pub success: bool,
+    pub status: Option<String>,
+    pub duration: Option<Duration>,
+    pub output: Vec<String>,
+}
+
+#[derive(Debug)]
+struct Run {
+    job: String,
+    user: Option<String>,
+    started: DateTime<Utc>,
+    status: Option<String>,
+    output: VecDeque<String>,
+}
+
+impl Run {
+    fn new(job: String, user: Option<String>, started: DateTime<Utc>) -> Self {
+        Run {
+            job,
+            user,
+            started,
+            status: None,
+            output: VecDeque::new(),
+        }
+    }
+    fn finish(self, success: bool, time: DateTime<Utc>) -> Outcome {
+        Outcome {
+            job: self.job,
+            user: self.user,
+            success,
+            status: self.status,
+            duration: Some(time - self.started),
+            output: self.output.into(),
+        }
+    }
+}
+
+/// Follows the runs in progress, keeping the last `output_lines` of their output.
+/// Cron runs are forgotten after `ttl`, as cron doesn't log the end of every run
+pub struct Runs {
+    cron: HashMap<String, Run>,
+    units: HashMap<String, Run>,
+    output_lines: usize,
+    ttl: Duration,
+}
+
+impl Runs {
+    pub fn new(output_lines: usize, ttl: Duration) -> Self {
+        Runs {
+            cron: HashMap::new(),
+            units: HashMap::new(),
+            output_lines,
+            ttl,
+        }
+    }
+    pub fn handle(&mut self, event: JobEvent, time: DateTime<Utc>) -> Option<Outcome> {
+        match event {
+            JobEvent::CronStart { pid, user, command } => {
+                let ttl = self.ttl;
+                self.cron.retain(|_, run| time - run.started < ttl);
+                self.cron.insert(pid, Run::new(command, Some(user), time));
+                None
+            }
+            JobEvent::CronOutput { pid, line } => {
+                let output_lines = self.output_lines;
+                if let Some(run) = self.cron.get_mut(&pid) {
+                    push_line(&mut run.output, line, output_lines);
+                }
+                None
+            }
+            JobEvent::CronFailed { pid, status } => {
+                let mut run = self.cron.remove(&pid)?;
+                run.status = Some(format!("exit status {}", status));
+                Some(run.finish(false, time))
+            }
+            JobEvent::CronEnd { pid } => {
+                let run = self.cron.remove(&pid)?;
+                Some(run.finish(true, time))
+            }
+            JobEvent::UnitStart { unit } => {
+                self.units.insert(unit.clone(), Run::new(unit, None, time));
+                None
+            }
+            JobEvent::UnitOutput { unit, line } => {
+                let output_lines = self.output_lines;
+                if let Some(run) = self.units.get_mut(&unit) {
+                    push_line(&mut run.output, line, output_lines);
+                }
+                None
+            }
+            JobEvent::UnitExit { unit, status } => {
+                if let Some(run) = self.units.get_mut(&unit) {
+                    run.status = Some(status);
+                }
+                None
+            }
+            JobEvent::UnitResult { unit, result } => {
+                let success = result == "success";
+                match self.units.remove(&unit) {
+                    Some(mut run) => {
+                        if !success && run.status.is_none() {
+                            run.status = Some(result);
+                        }
+                        Some(run.finish(success, time))
+                    }
+                    // the run was started before logram
+                    None => Some(Outcome {
+                        job: unit,
+                        user: None,
+                        success,
+                        status: if success { None } else { Some(result) },
+                        duration: None,
+                        output: Vec::new(),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+fn push_line(output: &mut VecDeque<String>, line: String, limit: usize) {
+    output.push_back(line);
+    if output.len() > limit {
+        output.pop_front();
+    }
+}
+
+/// Expected schedules, a schedule is missed when its job didn't start for longer than `every`
+pub struct Schedules {
+    entries: Vec<(Schedule, DateTime<Utc>, bool)>,
+}
+
+impl Schedules {
+    pub fn new(schedules: Vec<Schedule>, now: DateTime<Utc>) -> Self {
+        let entries = schedules
+            .into_iter()
+            .map(|schedule| (schedule, now, false))
+            .collect();
+
+        Schedules { entries }
+    }
+    pub fn started(&mut self, job: &str, time: DateTime<Utc>) {
+        for (schedule, last_run, missed) in &mut self.entries {
+            if job.contains(&schedule.job) {
+                *last_run = time;
+                *missed = false;
+            }
+        }
+    }
+    /// Returns the newly missed schedules along with the time of the last run
+    pub fn missed(&mut self, now: DateTime<Utc>) -> Vec<(Schedule, DateTime<Utc>)> {
+        let mut result = Vec::new();
+
+        for (schedule, last_run, missed) in &mut self.entries {
+            let every = Duration::seconds(schedule.every as i64);
+            if !*missed && now - *last_run > every {
+                *missed = true;
+                result.push((schedule.clone(), *last_run));
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::{
+        super::{config::Schedule, events::JobEvent},
+        Outcome, Runs, Schedules,
+    };
+
+    #[test]
+    fn main() {
+        let mut runs = Runs::new(2, Duration::days(1));
+        let start = Utc.ymd(2021, 1, 2).and_hms(3, 0, 0);
+        let pid = || String::from("42");
+
+        let event = JobEvent::CronStart {
+            pid: pid(),
+            user: String::from("root"),
+            command: String::from("/usr/local/bin/rotate.sh"),
+        };
+        assert_eq!(runs.handle(event, start), None);
+        for line in &["one", "two", "three"] {
+            let event = JobEvent::CronOutput {
+                pid: pid(),
+                line: line.to_string(),
+            };
+            assert_eq!(runs.handle(event, start), None);
+        }
+
+        let event = JobEvent::CronFailed {
+            pid: pid(),
+            status: String::from("2"),
+        };
+        let expected = Outcome {
+            job: String::from("/usr/local/bin/rotate.sh"),
+            user: Some(String::from("root")),
+            success: false,
+            status: Some(String::from("exit status 2")),
+            duration: Some(Duration::seconds(65)),
+            output: vec![String::from("two"), String::from("three")],
+        };
+        let end = start + Duration::seconds(65);
+        assert_eq!(runs.handle(event, end), Some(expected));
+        // END after a failure is ignored
+        assert_eq!(runs.handle(JobEvent::CronEnd { pid: pid() }, end), None);
+
+        let unit = || String::from("backup.service");
+        runs.handle(JobEvent::UnitStart { unit: unit() }, start);
+        let event = JobEvent::UnitExit {
+            unit: unit(),
+            status: String::from("exited, status=1"),
+        };
+        runs.handle(event, end);
+        let event = JobEvent::UnitResult {
+            unit: unit(),
+            result: String::from("exit-code"),
+        };
+        let outcome = runs.handle(event, end).unwrap();
+        assert!(!outcome.success);
+        assert_eq!(outcome.status, Some(String::from("exited, status=1")));
+
+        let event = JobEvent::UnitResult {
+            unit: unit(),
+            result: String::from("success"),
+        };
+        let outcome = runs.handle(event, end).unwrap();
+        assert!(outcome.success);
+        assert_eq!(outcome.duration, None);
+    }
+
+    #[test]
+    fn expired_runs() {
+        let mut runs = Runs::new(2, Duration::hours(1));
+        let start = Utc.ymd(2021, 1, 2).and_hms(3, 0, 0);
+        let cron_start = |pid: &str| JobEvent::CronStart {
+            pid: pid.to_owned(),
+            user: String::from("root"),
+            command: String::from("/usr/local/bin/sync.sh"),
+        };
+
+        runs.handle(cron_start("1"), start);
+        runs.handle(cron_start("2"), start + Duration::minutes(30));
+        runs.handle(cron_start("3"), start + Duration::minutes(61));
+        assert_eq!(runs.cron.len(), 2);
+
+        let end = JobEvent::CronEnd {
+            pid: String::from("1"),
+        };
+        assert_eq!(runs.handle(end, start + Duration::minutes(62)), None);
+        let end = JobEvent::CronEnd {
+            pid: String::from("2"),
+        };
+        assert!(runs.handle(end, start + Duration::minutes(62)).is_some());
+    }
+
+    #[test]
+    fn schedules() {
+        let start = Utc.ymd(2021, 1, 2).and_hms(3, 0, 0);
+        let schedule = Schedule {
+            job: String::from("backup"),
+            every: 3600,
+        };
+        let mut schedules = Schedules::new(vec![schedule], start);
+
+        assert!(schedules.missed(start + Duration::minutes(50)).is_empty());
+        schedules.started("backup.service", start + Duration::minutes(55));
+        assert!(schedules.missed(start + Duration::minutes(100)).is_empty());
+
+        let missed = schedules.missed(start + Duration::minutes(116));
+        assert_eq!(missed.len(), 1);
+        assert_eq!(missed[0].1, start + Duration::minutes(55));
+        // reported once
+        assert!(schedules.missed(start + Duration::minutes(200)).is_empty());
+
+        schedules.started("/usr/local/bin/backup.sh", start + Duration::minutes(201));
+        assert!(schedules.missed(start + Duration::minutes(202)).is_empty());
+    }
+}
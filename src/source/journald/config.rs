@@ -1,6 +1,8 @@
 use serde::Deserialize;
 use std::collections::HashMap;
 
+use crate::source::OverflowPolicy;
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct MatchGroup {
     pub title: String,
@@ -11,12 +13,16 @@ pub struct MatchGroup {
 #[serde(default)]
 pub struct JournaldLogSourceConfig {
     pub matches: Vec<MatchGroup>,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
 }
 
 impl Default for JournaldLogSourceConfig {
     fn default() -> Self {
         JournaldLogSourceConfig {
             matches: Vec::new(),
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
         }
     }
 }
@@ -1,27 +1,24 @@
 use anyhow::Result;
 use futures::{
-    channel::{
-        mpsc::{self as futures_mpsc, Receiver, Sender},
-        oneshot::{self, Sender as OneshotSender},
-    },
+    channel::oneshot::{self, Sender as OneshotSender},
     executor,
 };
 use std::{iter::Iterator, thread};
 use systemd::journal::{Journal, JournalFiles, JournalRecord, JournalSeek};
 
-use crate::source::{LogRecord, LogSource, LogSourceStream};
+use crate::source::{BlockingReceiver, BlockingSender, LogRecord, LogSource, LogSourceStream};
 
 mod config;
 pub use self::config::{JournaldLogSourceConfig, MatchGroup};
 
 pub struct JournaldLogSource {
-    receiver: Receiver<Result<LogRecord>>,
+    receiver: BlockingReceiver,
 }
 
 impl JournaldLogSource {
     pub fn new(config: JournaldLogSourceConfig) -> Result<Self> {
         let (init_tx, init_rx) = oneshot::channel();
-        let (record_tx, record_rx) = futures_mpsc::channel(10);
+        let (record_tx, record_rx) = BlockingSender::channel(config.buffer, config.overflow);
 
         thread::spawn(move || run_inner(config, init_tx, record_tx));
 
@@ -51,7 +48,7 @@ impl JournaldLogSourceInner {
 
         for (matc, is_last) in with_last(config.matches.iter()) {
             for (key, value) in &matc.filters {
-                journal.match_add(&key, value.clone())?;
+                journal.match_add(key, value.clone())?;
             }
 
             if !is_last {
@@ -103,7 +100,7 @@ impl JournaldLogSourceInner {
 fn run_inner(
     config: JournaldLogSourceConfig,
     init_tx: OneshotSender<Result<()>>,
-    mut record_tx: Sender<Result<LogRecord>>,
+    mut record_tx: BlockingSender,
 ) {
     let mut inner = match JournaldLogSourceInner::new(config) {
         Ok(inner) => {
@@ -118,8 +115,8 @@ fn run_inner(
 
     loop {
         let record = inner.next_record();
-        if let Err(error) = record_tx.try_send(record) {
-            eprintln!("Error: {}", error);
+        if record_tx.send(record).is_err() {
+            break;
         }
     }
 }
@@ -164,6 +161,7 @@ mod tests {
                     filters: filters_b,
                 },
             ],
+            ..Default::default()
         };
 
         let source = JournaldLogSource::new(config).unwrap();
@@ -0,0 +1,30 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::source::{Level, OverflowPolicy};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct KmsgLogSourceConfig {
+    pub path: PathBuf,
+    pub title: String,
+    pub priority: Level,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub buffer: usize,
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for KmsgLogSourceConfig {
+    fn default() -> Self {
+        KmsgLogSourceConfig {
+            path: PathBuf::from("/dev/kmsg"),
+            title: String::from("Kernel"),
+            priority: Level::Warning,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            buffer: 10,
+            overflow: OverflowPolicy::Block,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use regex::Regex;
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, ErrorKind, Seek, SeekFrom},
+    thread,
+    time::Duration,
+};
+
+use crate::source::{BlockingSender, Level, LogRecord, LogSource, LogSourceStream, OverflowPolicy};
+
+mod config;
+mod parser;
+pub use self::config::KmsgLogSourceConfig;
+use self::parser::KmsgRecord;
+
+pub struct KmsgLogSource {
+    reader: BufReader<File>,
+    boot_time: DateTime<Utc>,
+    title: String,
+    priority: Level,
+    include: Vec<Regex>,
+    exclude: Vec<Regex>,
+    buffer: usize,
+    overflow: OverflowPolicy,
+}
+
+impl KmsgLogSource {
+    pub fn new(config: KmsgLogSourceConfig) -> Result<Self> {
+        let mut file = File::open(&config.path)?;
+        file.seek(SeekFrom::End(0))?;
+
+        let compile = |patterns: Vec<String>| -> Result<Vec<Regex>> {
+            patterns
+                .iter()
+                .map(|pattern| Regex::new(pattern).map_err(Into::into))
+                .collect()
+        };
+
+        Ok(KmsgLogSource {
+            reader: BufReader::new(file),
+            boot_time: boot_time()?,
+            title: config.title,
+            priority: config.priority,
+            include: compile(config.include)?,
+            exclude: compile(config.exclude)?,
+            buffer: config.buffer,
+            overflow: config.overflow,
+        })
+    }
+    fn next_record(&mut self) -> Result<LogRecord> {
+        loop {
+            let mut line = String::new();
+
+            match self.reader.read_line(&mut line) {
+                // /dev/kmsg blocks on reading, EOF is possible only for regular files
+                Ok(0) => thread::sleep(Duration::from_millis(100)),
+                Ok(_) => {
+                    // continuation lines with the device properties
+                    if line.starts_with(' ') {
+                        continue;
+                    }
+
+                    let record = parser::parse(&line)
+                        .ok_or_else(|| anyhow!("Invalid kmsg record: {}", line))?;
+                    if self.is_matched(&record) {
+                        return Ok(self.to_log_record(record));
+                    }
+                }
+                // some records were overwritten in the kernel ring buffer
+                Err(error) if error.kind() == ErrorKind::BrokenPipe => continue,
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+    fn is_matched(&self, record: &KmsgRecord) -> bool {
+        let level = Level::from_syslog(record.severity).unwrap_or(Level::Debug);
+        let message = &record.message;
+
+        level <= self.priority
+            && (self.include.is_empty() || self.include.iter().any(|re| re.is_match(message)))
+            && !self.exclude.iter().any(|re| re.is_match(message))
+    }
+    fn to_log_record(&self, record: KmsgRecord) -> LogRecord {
+        let since_boot =
+            ChronoDuration::from_std(record.since_boot).unwrap_or_else(|_| ChronoDuration::zero());
+        let mut log_record = LogRecord::new(self.title.clone(), record.message)
+            .with_timestamp(self.boot_time + since_boot)
+            .with_metadata("sequence", record.sequence.to_string());
+
+        if let Some(level) = Level::from_syslog(record.severity) {
+            log_record = log_record.with_level(level);
+        }
+
+        log_record
+    }
+}
+
+impl LogSource for KmsgLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (mut sender, rx) = BlockingSender::channel(self.buffer, self.overflow);
+
+        thread::spawn(move || {
+            let mut source = self;
+
+            loop {
+                let record = source.next_record();
+                if sender.send(record).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Box::pin(rx)
+    }
+}
+
+/// Calculates the boot time from `/proc/uptime`, the kmsg timestamps are relative to it
+fn boot_time() -> Result<DateTime<Utc>> {
+    let uptime = fs::read_to_string("/proc/uptime")?;
+    let seconds: f64 = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid /proc/uptime: {}", uptime))?;
+
+    Ok(Utc::now() - ChronoDuration::milliseconds((seconds * 1000.0) as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, File},
+        io::Write,
+    };
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{KmsgLogSource, KmsgLogSourceConfig};
+
+    static FIXTURE: &str = include_str!("fixtures/kmsg.txt");
+
+    #[tokio::test]
+    async fn main() {
+        let path = env::temp_dir().join("logram_test_kmsg");
+        if path.exists() {
+            fs::remove_file(&path).unwrap();
+        }
+        let mut file = File::create(&path).unwrap();
+
+        let config = KmsgLogSourceConfig {
+            path: path.clone(),
+            exclude: vec![String::from("^mce: ")],
+            ..Default::default()
+        };
+
+        let source = KmsgLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+
+        file.write_all(FIXTURE.as_bytes()).unwrap();
+
+        let mut actual: Vec<LogRecord> = stream.take(4).map(Result::unwrap).collect().await;
+        for record in &mut actual {
+            assert!(record.timestamp.take().is_some());
+        }
+
+        let record = |level, sequence, message| {
+            LogRecord::new("Kernel", message)
+                .with_level(level)
+                .with_metadata("sequence", sequence)
+        };
+        let expected = vec![
+            record(
+                Level::Warning,
+                "1432",
+                "EXT4-fs (sda1): warning: mounting fs with errors, running e2fsck is recommended",
+            ),
+            record(
+                Level::Error,
+                "1433",
+                "EXT4-fs error (device sda1): ext4_lookup:1701: inode #1835010: \
+                 comm ls: deleted inode referenced: 1835266",
+            ),
+            record(
+                Level::Error,
+                "1502",
+                "INFO: task kworker/u16:2:2514 blocked for more than 120 seconds.",
+            ),
+            record(
+                Level::Error,
+                "1701",
+                "Out of memory: Killed process 4411 (java) total-vm:8394812kB, \
+                 anon-rss:6291456kB, file-rss:0kB, shmem-rss:0kB, UID:1000 \
+                 pgtables:12780kB oom_score_adj:0",
+            ),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
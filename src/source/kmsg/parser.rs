@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+pub struct KmsgRecord {
+    pub facility: u8,
+    pub severity: u8,
+    pub sequence: u64,
+    pub since_boot: Duration,
+    pub message: String,
+}
+
+/// Parses `priority,sequence,timestamp,flags;message` line of `/dev/kmsg`,
+/// the message is unescaped from `\xNN` sequences
+pub fn parse(line: &str) -> Option<KmsgRecord> {
+    let (prefix, message) = line.split_once(';')?;
+    let mut fields = prefix.split(',');
+
+    let priority: u16 = fields.next()?.parse().ok()?;
+    let sequence = fields.next()?.parse().ok()?;
+    let timestamp = fields.next()?.parse().ok()?;
+
+    Some(KmsgRecord {
+        facility: (priority >> 3) as u8,
+        severity: (priority & 7) as u8,
+        sequence,
+        since_boot: Duration::from_micros(timestamp),
+        message: unescape(message.trim_end_matches('\n')),
+    })
+}
+
+fn unescape(message: &str) -> String {
+    let mut bytes = Vec::with_capacity(message.len());
+    let mut rest = message.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let code = tail
+            .strip_prefix(b"x")
+            .and_then(|hex| hex.get(..2))
+            .and_then(|hex| u8::from_str_radix(&String::from_utf8_lossy(hex), 16).ok());
+
+        match code {
+            Some(code) if byte == b'\\' => {
+                bytes.push(code);
+                rest = &tail[3..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{parse, KmsgRecord};
+
+    static FIXTURE: &str = include_str!("fixtures/kmsg.txt");
+
+    #[test]
+    fn fixture() {
+        let records: Vec<KmsgRecord> = FIXTURE
+            .lines()
+            .filter(|line| !line.starts_with(' '))
+            .map(|line| parse(line).unwrap())
+            .collect();
+
+        assert_eq!(records.len(), 10);
+        assert_eq!(
+            records[2],
+            KmsgRecord {
+                facility: 0,
+                severity: 3,
+                sequence: 1433,
+                since_boot: Duration::from_micros(512_334_982),
+                message: String::from(
+                    "EXT4-fs error (device sda1): ext4_lookup:1701: inode #1835010: \
+                     comm ls: deleted inode referenced: 1835266"
+                ),
+            }
+        );
+        assert_eq!(records[6].severity, 0);
+        assert_eq!(records[9].facility, 3);
+        assert_eq!(records[9].severity, 6);
+    }
+
+    #[test]
+    fn escaped() {
+        let record = parse("6,5,100,-;tab\\x09and backslash \\x5c, not \\xZZ").unwrap();
+
+        assert_eq!(record.message, "tab\tand backslash \\, not \\xZZ");
+        assert!(parse("garbage").is_none());
+    }
+}
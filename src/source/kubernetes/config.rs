@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use super::filter::PodFilter;
+use crate::source::ParserConfig;
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct KubeletConfig {
+    pub url: String,
+    pub token_file: Option<PathBuf>,
+    pub insecure: bool,
+    pub timeout: u64,
+}
+
+impl Default for KubeletConfig {
+    fn default() -> Self {
+        KubeletConfig {
+            url: String::from("https://127.0.0.1:10250/pods"),
+            token_file: Some(PathBuf::from(
+                "/var/run/secrets/kubernetes.io/serviceaccount/token",
+            )),
+            insecure: true,
+            timeout: 10,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct KubernetesLogSourceConfig {
+    pub path: PathBuf,
+    pub interval: u64,
+    pub include: Vec<PodFilter>,
+    pub exclude: Vec<PodFilter>,
+    pub kubelet: Option<KubeletConfig>,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
+}
+
+impl Default for KubernetesLogSourceConfig {
+    fn default() -> Self {
+        KubernetesLogSourceConfig {
+            path: PathBuf::from("/var/log/containers"),
+            interval: 1000,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            kubelet: None,
+            parser: ParserConfig::default(),
+        }
+    }
+}
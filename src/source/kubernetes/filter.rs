@@ -0,0 +1,125 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use crate::source::glob::glob_match;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct PodFilter {
+    pub namespace: Option<String>,
+    pub pod: Option<String>,
+    pub container: Option<String>,
+    pub label: Option<String>,
+}
+
+impl PodFilter {
+    /// Checks that the container satisfies all of the specified conditions
+    fn matches(&self, container: &PodContainer) -> bool {
+        let globs = [
+            (&self.namespace, &container.namespace),
+            (&self.pod, &container.pod),
+            (&self.container, &container.name),
+        ];
+        for (pattern, value) in &globs {
+            if let Some(pattern) = pattern {
+                if !glob_match(pattern, value) {
+                    return false;
+                }
+            }
+        }
+
+        if let Some(selector) = &self.label {
+            let label = |key: &str| container.labels.get(key).map(String::as_str);
+            let matched = match selector.split_once('=') {
+                Some((key, value)) => label(key) == Some(value),
+                None => label(selector).is_some(),
+            };
+            if !matched {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PodFilters {
+    pub include: Vec<PodFilter>,
+    pub exclude: Vec<PodFilter>,
+}
+
+impl PodFilters {
+    pub fn matches(&self, container: &PodContainer) -> bool {
+        let included = self.include.is_empty() || self.include.iter().any(|f| f.matches(container));
+        let excluded = self.exclude.iter().any(|f| f.matches(container));
+
+        included && !excluded
+    }
+    pub fn uses_labels(&self) -> bool {
+        let filters = self.include.iter().chain(&self.exclude);
+        filters.into_iter().any(|filter| filter.label.is_some())
+    }
+}
+
+#[derive(Debug)]
+pub struct PodContainer<'a> {
+    pub namespace: &'a str,
+    pub pod: &'a str,
+    pub name: &'a str,
+    pub labels: &'a HashMap<String, String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{PodContainer, PodFilter, PodFilters};
+
+    #[test]
+    fn main() {
+        let mut labels = HashMap::new();
+        labels.insert(String::from("app"), String::from("api"));
+        let container = PodContainer {
+            namespace: "shop",
+            pod: "api-7d9c5-x2x4z",
+            name: "api",
+            labels: &labels,
+        };
+
+        assert!(PodFilters::default().matches(&container));
+
+        let by_namespace = PodFilters {
+            include: vec![PodFilter {
+                namespace: Some(String::from("shop")),
+                ..Default::default()
+            }],
+            exclude: vec![PodFilter {
+                container: Some(String::from("*-sidecar")),
+                ..Default::default()
+            }],
+        };
+        assert!(by_namespace.matches(&container));
+        assert!(!by_namespace.uses_labels());
+
+        let by_label = PodFilters {
+            include: vec![PodFilter {
+                label: Some(String::from("app=web")),
+                ..Default::default()
+            }],
+            exclude: Vec::new(),
+        };
+        assert!(!by_label.matches(&container));
+        assert!(by_label.uses_labels());
+
+        let excluded = PodFilters {
+            include: Vec::new(),
+            exclude: vec![PodFilter {
+                namespace: Some(String::from("shop")),
+                label: Some(String::from("app")),
+                ..Default::default()
+            }],
+        };
+        assert!(!excluded.matches(&container));
+    }
+}
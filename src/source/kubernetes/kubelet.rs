@@ -0,0 +1,153 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::Deserialize;
+use std::{collections::HashMap, fs, path::PathBuf, time::Duration};
+
+use super::config::KubeletConfig;
+
+pub type Labels = HashMap<String, String>;
+
+#[derive(Debug, Deserialize)]
+struct PodList {
+    items: Vec<Pod>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pod {
+    metadata: PodMetadata,
+}
+
+#[derive(Debug, Deserialize)]
+struct PodMetadata {
+    name: String,
+    namespace: String,
+    #[serde(default)]
+    labels: Labels,
+}
+
+/// Client of the kubelet API, which knows the labels of the pods on the node
+pub struct Kubelet {
+    client: Client,
+    url: String,
+    token_file: Option<PathBuf>,
+}
+
+impl Kubelet {
+    pub fn new(config: KubeletConfig) -> Result<Self> {
+        let client = Client::builder()
+            .danger_accept_invalid_certs(config.insecure)
+            .timeout(Duration::from_secs(config.timeout))
+            .build()?;
+
+        Ok(Kubelet {
+            client,
+            url: config.url,
+            token_file: config.token_file,
+        })
+    }
+    /// Returns the labels of the pods by namespace and name
+    pub async fn pod_labels(&self) -> Result<HashMap<(String, String), Labels>> {
+        let mut request = self.client.get(&self.url);
+        // service account tokens are rotated, so the token is read each time
+        if let Some(path) = &self.token_file {
+            let token = fs::read_to_string(path)?;
+            request = request.bearer_auth(token.trim());
+        }
+
+        let pods: PodList = request.send().await?.error_for_status()?.json().await?;
+        let labels = pods
+            .items
+            .into_iter()
+            .map(|pod| {
+                let metadata = pod.metadata;
+                ((metadata.namespace, metadata.name), metadata.labels)
+            })
+            .collect();
+
+        Ok(labels)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    use super::{super::config::KubeletConfig, Kubelet};
+
+    pub const PODS: &str = r#"{"kind":"PodList","items":[
+        {"metadata":{"name":"api-7d9c5-x2x4z","namespace":"shop","labels":{"app":"api"}}},
+        {"metadata":{"name":"db-0","namespace":"shop"}}
+    ]}"#;
+
+    /// Serves the pod list to every request, returns the URL
+    pub fn serve() -> String {
+        serve_lists(vec![PODS])
+    }
+
+    /// Serves the pod lists to the requests in turn, repeating the last one, returns the URL
+    pub fn serve_lists(lists: Vec<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for (number, stream) in listener.incoming().enumerate() {
+                let mut stream = stream.unwrap();
+                let mut buffer = [0; 1024];
+                let _ = stream.read(&mut buffer).unwrap();
+
+                let pods = lists[number.min(lists.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    pods.len(),
+                    pods
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+
+        format!("http://{}/pods", addr)
+    }
+
+    #[tokio::test]
+    async fn main() {
+        let kubelet = Kubelet::new(KubeletConfig {
+            url: serve(),
+            token_file: None,
+            insecure: false,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let labels = kubelet.pod_labels().await.unwrap();
+        let key = (String::from("shop"), String::from("api-7d9c5-x2x4z"));
+        assert_eq!(labels[&key]["app"], "api");
+        let key = (String::from("shop"), String::from("db-0"));
+        assert!(labels[&key].is_empty());
+    }
+
+    #[tokio::test]
+    async fn timeout() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        // accepts the connection and never responds
+        thread::spawn(move || {
+            let _stream = listener.accept().unwrap();
+            thread::sleep(Duration::from_secs(5));
+        });
+
+        let kubelet = Kubelet::new(KubeletConfig {
+            url: format!("http://{}/pods", addr),
+            token_file: None,
+            insecure: false,
+            timeout: 1,
+        })
+        .unwrap();
+
+        assert!(kubelet.pod_labels().await.is_err());
+    }
+}
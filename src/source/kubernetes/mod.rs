@@ -0,0 +1,369 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{Duration, Instant},
+};
+use tokio::time::delay_for;
+
+use crate::source::{
+    cri::{CriEntry, CriLogs},
+    LogRecord, LogSource, LogSourceStream, Parser,
+};
+
+mod config;
+mod filter;
+mod kubelet;
+pub use self::config::KubernetesLogSourceConfig;
+use self::{
+    filter::{PodContainer, PodFilters},
+    kubelet::{Kubelet, Labels},
+};
+
+/// Minimal interval between requests to kubelet for the labels of pods,
+/// which kubelet didn't know at the last request, or after a failed request
+const LABELS_REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+type RecordSender = Sender<Result<LogRecord>>;
+
+/// Reads the logs of the pods on the node from the CRI log files
+pub struct KubernetesLogSource {
+    logs: CriLogs,
+    interval: Duration,
+    parser: Parser,
+    filters: PodFilters,
+    kubelet: Option<Kubelet>,
+    labels: HashMap<(String, String), Labels>,
+    labels_refreshed: Option<Instant>,
+    /// Pods which were unknown to kubelet at the last request
+    missing: HashSet<(String, String)>,
+    refresh_failed: bool,
+}
+
+impl KubernetesLogSource {
+    pub fn new(config: KubernetesLogSourceConfig) -> Result<Self> {
+        let filters = PodFilters {
+            include: config.include,
+            exclude: config.exclude,
+        };
+        let kubelet = match config.kubelet {
+            Some(kubelet) => Some(Kubelet::new(kubelet)?),
+            None if filters.uses_labels() => {
+                return Err(anyhow!("Label filters require kubelet to be configured"))
+            }
+            None => None,
+        };
+
+        Ok(KubernetesLogSource {
+            logs: CriLogs::new(config.path)?,
+            interval: Duration::from_millis(config.interval),
+            parser: Parser::new(config.parser)?,
+            filters,
+            kubelet,
+            labels: HashMap::new(),
+            labels_refreshed: None,
+            missing: HashSet::new(),
+            refresh_failed: false,
+        })
+    }
+    /// Requests the labels from kubelet if the pod is unknown. A new pod is requested
+    /// at once, so its first lines aren't filtered without labels, while pods missing
+    /// in kubelet are requested again only after the refresh interval. After a failed
+    /// request the labels aren't requested until the refresh interval passes either,
+    /// so the error is reported once per refresh instead of once per line
+    async fn update_labels(&mut self, key: &(String, String)) -> Result<()> {
+        let kubelet = match &self.kubelet {
+            Some(kubelet) if self.filters.uses_labels() => kubelet,
+            _ => return Ok(()),
+        };
+        if self.labels.contains_key(key) {
+            return Ok(());
+        }
+
+        let refresh_due = match self.labels_refreshed {
+            Some(refreshed) => refreshed.elapsed() >= LABELS_REFRESH_INTERVAL,
+            None => true,
+        };
+        if (self.refresh_failed || self.missing.contains(key)) && !refresh_due {
+            return Ok(());
+        }
+
+        self.labels_refreshed = Some(Instant::now());
+        self.refresh_failed = true;
+        self.labels = kubelet.pod_labels().await?;
+        self.refresh_failed = false;
+
+        let labels = &self.labels;
+        self.missing.retain(|key| !labels.contains_key(key));
+        if !labels.contains_key(key) {
+            self.missing.insert(key.clone());
+        }
+
+        Ok(())
+    }
+    async fn handle_entry(&mut self, entry: CriEntry) -> Result<Option<LogRecord>> {
+        let container = &entry.container;
+        let key = match (&container.namespace, &container.pod) {
+            (Some(namespace), Some(pod)) => (namespace.clone(), pod.clone()),
+            // not a pod log file
+            _ => return Ok(None),
+        };
+
+        self.update_labels(&key).await?;
+
+        let no_labels = Labels::new();
+        let pod_container = PodContainer {
+            namespace: &key.0,
+            pod: &key.1,
+            name: &container.name,
+            labels: self.labels.get(&key).unwrap_or(&no_labels),
+        };
+        if !self.filters.matches(&pod_container) {
+            return Ok(None);
+        }
+
+        let title = format!("{}/{}/{}", key.0, key.1, container.name);
+        let mut record = LogRecord::new(title, entry.message)
+            .with_timestamp(entry.timestamp)
+            .with_metadata("stream", entry.stream)
+            .with_metadata("namespace", key.0)
+            .with_metadata("pod", key.1)
+            .with_metadata("container", container.name.clone());
+        if let Some(id) = &container.id {
+            record = record.with_metadata("container_id", id.clone());
+        }
+
+        Ok(Some(self.parser.parse_record(record)))
+    }
+}
+
+impl LogSource for KubernetesLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+async fn run(mut source: KubernetesLogSource, mut tx: RecordSender) {
+    loop {
+        let entries = match source.logs.poll() {
+            Ok(entries) => entries,
+            Err(error) => {
+                if tx.send(Err(error)).await.is_err() {
+                    return;
+                }
+                Vec::new()
+            }
+        };
+
+        for entry in entries {
+            let result = match source.handle_entry(entry).await {
+                Ok(Some(record)) => Ok(record),
+                Ok(None) => continue,
+                Err(error) => Err(error),
+            };
+
+            if tx.send(result).await.is_err() {
+                return;
+            }
+        }
+
+        delay_for(source.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+        net::TcpListener,
+        path::Path,
+    };
+
+    use crate::source::{LogRecord, LogSource};
+
+    use super::{
+        config::KubeletConfig,
+        filter::PodFilter,
+        kubelet::tests::{serve, serve_lists, PODS},
+        KubernetesLogSource, KubernetesLogSourceConfig,
+    };
+
+    fn append(path: &Path, line: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        writeln!(file, "{}", line).unwrap();
+    }
+
+    #[tokio::test]
+    async fn main() {
+        let dir = env::temp_dir().join("logram_test_kubernetes");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let api = dir.join(format!("api-7d9c5-x2x4z_shop_api-{}.log", id));
+        let db = dir.join(format!("db-0_shop_postgres-{}.log", id));
+        let system = dir.join(format!("coredns-1_kube-system_coredns-{}.log", id));
+        for path in &[&api, &db, &system] {
+            fs::write(path, "").unwrap();
+        }
+
+        let config = KubernetesLogSourceConfig {
+            path: dir.clone(),
+            interval: 100,
+            include: vec![PodFilter {
+                label: Some(String::from("app=api")),
+                ..Default::default()
+            }],
+            exclude: vec![PodFilter {
+                namespace: Some(String::from("kube-*")),
+                ..Default::default()
+            }],
+            kubelet: Some(KubeletConfig {
+                url: serve(),
+                token_file: None,
+                insecure: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let source = KubernetesLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        append(&system, "2021-01-02T03:04:05Z stdout F ignored namespace");
+        append(&db, "2021-01-02T03:04:05Z stdout F ignored label");
+        append(&api, "2021-01-02T03:04:06Z stderr P request ");
+        append(&api, "2021-01-02T03:04:06Z stderr F failed");
+
+        let record = stream.next().await.unwrap().unwrap();
+        let expected = LogRecord::new("shop/api-7d9c5-x2x4z/api", "request failed")
+            .with_timestamp(Utc.ymd(2021, 1, 2).and_hms(3, 4, 6))
+            .with_metadata("stream", "stderr")
+            .with_metadata("namespace", "shop")
+            .with_metadata("pod", "api-7d9c5-x2x4z")
+            .with_metadata("container", "api")
+            .with_metadata("container_id", id);
+        assert_eq!(record, expected);
+
+        let config = KubernetesLogSourceConfig {
+            path: dir.clone(),
+            include: vec![PodFilter {
+                label: Some(String::from("app")),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert!(KubernetesLogSource::new(config).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn new_pod() {
+        let dir = env::temp_dir().join("logram_test_kubernetes_new_pod");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let api = dir.join(format!("api-7d9c5-x2x4z_shop_api-{}.log", id));
+        fs::write(&api, "").unwrap();
+
+        // the worker pod is created after the first request to kubelet
+        let with_worker = r#"{"kind":"PodList","items":[
+            {"metadata":{"name":"api-7d9c5-x2x4z","namespace":"shop","labels":{"app":"api"}}},
+            {"metadata":{"name":"worker-0","namespace":"shop","labels":{"app":"api"}}}
+        ]}"#;
+        let config = KubernetesLogSourceConfig {
+            path: dir.clone(),
+            interval: 100,
+            include: vec![PodFilter {
+                label: Some(String::from("app=api")),
+                ..Default::default()
+            }],
+            kubelet: Some(KubeletConfig {
+                url: serve_lists(vec![PODS, with_worker]),
+                token_file: None,
+                insecure: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let source = KubernetesLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        append(&api, "2021-01-02T03:04:05Z stdout F from api");
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.body, "from api");
+
+        let worker = dir.join(format!("worker-0_shop_worker-{}.log", id));
+        fs::write(&worker, "").unwrap();
+        append(&worker, "2021-01-02T03:04:06Z stdout F from worker");
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.body, "from worker");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn failing_kubelet() {
+        let dir = env::temp_dir().join("logram_test_kubernetes_failing");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let id = "7d2f0e5e4f0a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4";
+        let api = dir.join(format!("api-7d9c5-x2x4z_shop_api-{}.log", id));
+        let worker = dir.join(format!("worker-0_shop_worker-{}.log", id));
+        fs::write(&api, "").unwrap();
+        fs::write(&worker, "").unwrap();
+
+        // nothing listens on the port
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/pods", listener.local_addr().unwrap());
+        drop(listener);
+
+        let config = KubernetesLogSourceConfig {
+            path: dir.clone(),
+            interval: 100,
+            exclude: vec![PodFilter {
+                label: Some(String::from("app=db")),
+                ..Default::default()
+            }],
+            kubelet: Some(KubeletConfig {
+                url,
+                token_file: None,
+                insecure: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let source = KubernetesLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        append(&api, "2021-01-02T03:04:05Z stdout F from api");
+        assert!(stream.next().await.unwrap().is_err());
+
+        // the other unknown pod doesn't request kubelet again
+        append(&worker, "2021-01-02T03:04:06Z stdout F from worker");
+        append(&api, "2021-01-02T03:04:07Z stdout F again from api");
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.body, "from worker");
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.body, "again from api");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
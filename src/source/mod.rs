@@ -1,12 +1,81 @@
 use anyhow::Result;
 use cfg_if::cfg_if;
 use futures::stream::{self, Stream};
+#[cfg(feature = "ls_stdin")]
+use futures::{future, StreamExt};
 use std::pin::Pin;
 
 mod config;
 mod record;
 pub use config::LogSourcesConfig;
-pub use record::LogRecord;
+pub use record::{Level, LogRecord};
+
+cfg_if! { if #[cfg(any(
+    feature = "ls_filesystem",
+    feature = "ls_journald",
+    feature = "ls_kmsg",
+    feature = "ls_auth",
+    feature = "ls_jobs",
+    feature = "ls_audit"
+))] {
+    mod sender;
+    pub use config::OverflowPolicy;
+    use sender::BlockingSender;
+}}
+
+#[cfg(any(
+    feature = "ls_journald",
+    feature = "ls_auth",
+    feature = "ls_jobs",
+    feature = "ls_audit"
+))]
+use sender::BlockingReceiver;
+
+#[cfg(any(feature = "ls_http", feature = "ls_alertmanager", feature = "ls_otlp"))]
+mod webhook;
+
+cfg_if! { if #[cfg(any(feature = "ls_docker", feature = "ls_exec"))] {
+    mod backoff;
+    use backoff::Backoff;
+}}
+
+cfg_if! { if #[cfg(any(
+    feature = "ls_filesystem",
+    feature = "ls_docker",
+    feature = "ls_pipe",
+    feature = "ls_containerd",
+    feature = "ls_kubernetes",
+    feature = "ls_stdin"
+))] {
+    mod parser;
+    pub use parser::ParserConfig;
+    use parser::Parser;
+}}
+
+#[cfg(any(
+    feature = "ls_filesystem",
+    feature = "ls_docker",
+    feature = "ls_pipe",
+    feature = "ls_containerd",
+    feature = "ls_kubernetes",
+    feature = "ls_stdin",
+    feature = "ls_syslog"
+))]
+mod timestamp;
+
+#[cfg(any(feature = "ls_containerd", feature = "ls_kubernetes"))]
+mod cri;
+
+#[cfg(any(feature = "ls_docker", feature = "ls_kubernetes"))]
+mod glob;
+
+#[cfg(any(
+    feature = "ls_auth",
+    feature = "ls_audit",
+    feature = "ls_containerd",
+    feature = "ls_kubernetes"
+))]
+mod tail;
 
 cfg_if! { if #[cfg(feature = "ls_counter")] {
     pub mod counter;
@@ -24,6 +93,81 @@ cfg_if! { if #[cfg(feature = "ls_docker")] {
     pub mod docker;
     use docker::DockerLogSource;
 }}
+cfg_if! { if #[cfg(feature = "ls_syslog")] {
+    pub mod syslog;
+    use syslog::SyslogLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_http")] {
+    pub mod http;
+    use http::HttpLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_alertmanager")] {
+    pub mod alertmanager;
+    use alertmanager::AlertmanagerLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_exec")] {
+    pub mod exec;
+    use exec::ExecLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_kmsg")] {
+    pub mod kmsg;
+    use kmsg::KmsgLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_pipe")] {
+    pub mod pipe;
+    use pipe::PipeLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_gelf")] {
+    pub mod gelf;
+    use gelf::GelfLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_sysmon")] {
+    pub mod sysmon;
+    use sysmon::SysmonLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_process")] {
+    pub mod process;
+    use process::ProcessLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_auth")] {
+    pub mod auth;
+    use auth::AuthLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_health")] {
+    pub mod health;
+    use health::HealthLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_certs")] {
+    pub mod certs;
+    use certs::CertsLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_jobs")] {
+    pub mod jobs;
+    use jobs::JobsLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_containerd")] {
+    pub mod containerd;
+    use containerd::ContainerdLogSource;
+}}
+cfg_if! { if #[cfg(feature = "ls_kubernetes")] {
+    pub mod kubernetes;
+    use kubernetes::KubernetesLogSource;
+}}
+
+cfg_if! { if #[cfg(feature = "ls_otlp")] {
+    pub mod otlp;
+    use otlp::OtlpLogSource;
+}}
+
+cfg_if! { if #[cfg(feature = "ls_stdin")] {
+    pub mod stdin;
+    use stdin::StdinLogSource;
+}}
+
+cfg_if! { if #[cfg(feature = "ls_audit")] {
+    pub mod audit;
+    use audit::AuditLogSource;
+}}
 
 pub type LogSourceStream = Pin<Box<dyn Stream<Item = Result<LogRecord>>>>;
 
@@ -58,5 +202,159 @@ pub fn init_log_sources(config: LogSourcesConfig) -> Result<LogSourceStream> {
         streams.push(filesystem.into_stream());
     }
 
+    #[cfg(feature = "ls_syslog")]
+    if config.syslog.enabled {
+        let syslog = SyslogLogSource::new(config.syslog.inner)?;
+        streams.push(syslog.into_stream());
+    }
+
+    #[cfg(feature = "ls_http")]
+    if config.http.enabled {
+        let http = HttpLogSource::new(config.http.inner)?;
+        streams.push(http.into_stream());
+    }
+
+    #[cfg(feature = "ls_alertmanager")]
+    if config.alertmanager.enabled {
+        let alertmanager = AlertmanagerLogSource::new(config.alertmanager.inner)?;
+        streams.push(alertmanager.into_stream());
+    }
+
+    #[cfg(feature = "ls_exec")]
+    if config.exec.enabled {
+        let exec = ExecLogSource::new(config.exec.inner);
+        streams.push(exec.into_stream());
+    }
+
+    #[cfg(feature = "ls_kmsg")]
+    if config.kmsg.enabled {
+        let kmsg = KmsgLogSource::new(config.kmsg.inner)?;
+        streams.push(kmsg.into_stream());
+    }
+
+    #[cfg(feature = "ls_pipe")]
+    if config.pipe.enabled {
+        let pipe = PipeLogSource::new(config.pipe.inner)?;
+        streams.push(pipe.into_stream());
+    }
+
+    #[cfg(feature = "ls_gelf")]
+    if config.gelf.enabled {
+        let gelf = GelfLogSource::new(config.gelf.inner)?;
+        streams.push(gelf.into_stream());
+    }
+
+    #[cfg(feature = "ls_sysmon")]
+    if config.sysmon.enabled {
+        let sysmon = SysmonLogSource::new(config.sysmon.inner)?;
+        streams.push(sysmon.into_stream());
+    }
+
+    #[cfg(feature = "ls_process")]
+    if config.process.enabled {
+        let process = ProcessLogSource::new(config.process.inner)?;
+        streams.push(process.into_stream());
+    }
+
+    #[cfg(feature = "ls_auth")]
+    if config.auth.enabled {
+        let auth = AuthLogSource::new(config.auth.inner)?;
+        streams.push(auth.into_stream());
+    }
+
+    #[cfg(feature = "ls_health")]
+    if config.health.enabled {
+        let health = HealthLogSource::new(config.health.inner)?;
+        streams.push(health.into_stream());
+    }
+
+    #[cfg(feature = "ls_certs")]
+    if config.certs.enabled {
+        let certs = CertsLogSource::new(config.certs.inner)?;
+        streams.push(certs.into_stream());
+    }
+
+    #[cfg(feature = "ls_jobs")]
+    if config.jobs.enabled {
+        let jobs = JobsLogSource::new(config.jobs.inner)?;
+        streams.push(jobs.into_stream());
+    }
+
+    #[cfg(feature = "ls_containerd")]
+    if config.containerd.enabled {
+        let containerd = ContainerdLogSource::new(config.containerd.inner)?;
+        streams.push(containerd.into_stream());
+    }
+
+    #[cfg(feature = "ls_kubernetes")]
+    if config.kubernetes.enabled {
+        let kubernetes = KubernetesLogSource::new(config.kubernetes.inner)?;
+        streams.push(kubernetes.into_stream());
+    }
+
+    #[cfg(feature = "ls_otlp")]
+    if config.otlp.enabled {
+        let otlp = OtlpLogSource::new(config.otlp.inner)?;
+        streams.push(otlp.into_stream());
+    }
+
+    #[cfg(feature = "ls_audit")]
+    if config.audit.enabled {
+        let audit = AuditLogSource::new(config.audit.inner)?;
+        streams.push(audit.into_stream());
+    }
+
+    #[cfg(feature = "ls_stdin")]
+    if config.stdin.enabled {
+        let stdin = StdinLogSource::new(config.stdin.inner)?;
+        let others = std::mem::take(&mut streams);
+        streams.push(until_end_of(stdin.into_stream(), others));
+    }
+
     Ok(Box::pin(stream::select_all(streams)))
 }
+
+/// Merges the streams like `select_all`, but ends as soon as the main stream ends
+#[cfg(feature = "ls_stdin")]
+fn until_end_of(main: LogSourceStream, others: Vec<LogSourceStream>) -> LogSourceStream {
+    let main = main.map(Some).chain(stream::once(future::ready(None)));
+    let others = stream::select_all(others).map(Some);
+
+    let merged = stream::select(main, others)
+        .take_while(|item| future::ready(item.is_some()))
+        .filter_map(future::ready);
+
+    Box::pin(merged)
+}
+
+#[cfg(all(test, feature = "ls_stdin"))]
+mod tests {
+    use futures::{stream, StreamExt};
+    use std::time::Duration;
+    use tokio::time;
+
+    use super::{until_end_of, LogRecord, LogSourceStream};
+
+    #[tokio::test]
+    async fn main() {
+        let main: LogSourceStream = Box::pin(stream::iter(vec![
+            Ok(LogRecord::only_title(String::from("first"))),
+            Ok(LogRecord::only_title(String::from("second"))),
+        ]));
+        let other: LogSourceStream = Box::pin(stream::iter(vec![Ok(LogRecord::only_title(
+            String::from("other"),
+        ))]));
+        let merged = until_end_of(main, vec![other, Box::pin(stream::pending())]);
+
+        // the pending stream doesn't keep the merged one open
+        let records = time::timeout(Duration::from_secs(1), merged.collect::<Vec<_>>())
+            .await
+            .unwrap();
+        let mut titles: Vec<String> = records
+            .into_iter()
+            .map(|record| record.unwrap().title)
+            .collect();
+        titles.sort();
+        assert_eq!(titles, ["first", "other", "second"]);
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+use std::net::SocketAddr;
+
+use crate::source::Level;
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct OtlpLogSourceConfig {
+    pub addr: SocketAddr,
+    pub path: String,
+    pub token: Option<String>,
+    pub max_body: usize,
+    pub priority: Level,
+}
+
+impl Default for OtlpLogSourceConfig {
+    fn default() -> Self {
+        OtlpLogSourceConfig {
+            addr: SocketAddr::from(([127, 0, 0, 1], 4318)),
+            path: String::from("/v1/logs"),
+            token: None,
+            max_body: 1024 * 1024,
+            priority: Level::Debug,
+        }
+    }
+}
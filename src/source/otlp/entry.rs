@@ -0,0 +1,146 @@
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+
+use crate::source::{Level, LogRecord};
+
+pub type Attributes = Vec<(String, Value)>;
+
+/// OTLP log record along with its resource and scope, decoded from either encoding
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LogEntry {
+    pub resource: Attributes,
+    pub scope: String,
+    pub time_unix_nano: u64,
+    pub observed_time_unix_nano: u64,
+    pub severity_number: u64,
+    pub severity_text: String,
+    pub body: Value,
+    pub attributes: Attributes,
+    pub trace_id: String,
+    pub span_id: String,
+}
+
+impl LogEntry {
+    /// Maps the OTLP severity number ranges (TRACE, DEBUG, INFO, WARN, ERROR and FATAL),
+    /// falls back to the severity text
+    pub fn level(&self) -> Option<Level> {
+        match self.severity_number {
+            1..=8 => Some(Level::Debug),
+            9..=12 => Some(Level::Info),
+            13..=16 => Some(Level::Warning),
+            17..=20 => Some(Level::Error),
+            21..=24 => Some(Level::Critical),
+            _ => self.severity_text.parse().ok(),
+        }
+    }
+    pub fn into_record(self) -> LogRecord {
+        let level = self.level();
+
+        let service = self
+            .resource
+            .iter()
+            .find(|(key, _)| key == "service.name")
+            .map(|(_, value)| value_to_string(value));
+        let title = match service {
+            Some(service) => service,
+            None if !self.scope.is_empty() => self.scope.clone(),
+            None => String::from("OTLP"),
+        };
+
+        let mut record = LogRecord::new(title, value_to_string(&self.body));
+        if let Some(level) = level {
+            record = record.with_level(level);
+        }
+
+        let nanos = match self.time_unix_nano {
+            0 => self.observed_time_unix_nano,
+            nanos => nanos,
+        };
+        if nanos > 0 {
+            let seconds = (nanos / 1_000_000_000) as i64;
+            if let Some(time) = Utc
+                .timestamp_opt(seconds, (nanos % 1_000_000_000) as u32)
+                .single()
+            {
+                record = record.with_timestamp(time);
+            }
+        }
+
+        for (key, value) in self.resource {
+            if key != "service.name" {
+                record = record.with_metadata(format!("resource.{}", key), value_to_string(&value));
+            }
+        }
+        for (key, value) in self.attributes {
+            record = record.with_metadata(key, value_to_string(&value));
+        }
+        if !self.trace_id.is_empty() {
+            record = record.with_metadata("trace_id", self.trace_id);
+        }
+        if !self.span_id.is_empty() {
+            record = record.with_metadata("span_id", self.span_id);
+        }
+
+        record
+    }
+}
+
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use crate::source::{Level, LogRecord};
+
+    use super::LogEntry;
+
+    #[test]
+    fn main() {
+        let entry = LogEntry {
+            resource: vec![
+                (String::from("service.name"), json!("checkout")),
+                (String::from("host.name"), json!("web-1")),
+            ],
+            scope: String::from("checkout.payments"),
+            time_unix_nano: 1_609_556_645_123_000_000,
+            severity_number: 17,
+            severity_text: String::from("ERROR"),
+            body: json!("payment failed"),
+            attributes: vec![
+                (String::from("order.id"), json!(42)),
+                (String::from("retry"), json!(true)),
+            ],
+            trace_id: String::from("5b8efff798038103d269b633813fc60c"),
+            ..Default::default()
+        };
+
+        let expected = LogRecord::new("checkout", "payment failed")
+            .with_level(Level::Error)
+            .with_timestamp(Utc.timestamp_millis(1_609_556_645_123))
+            .with_metadata("resource.host.name", "web-1")
+            .with_metadata("order.id", "42")
+            .with_metadata("retry", "true")
+            .with_metadata("trace_id", "5b8efff798038103d269b633813fc60c");
+        assert_eq!(entry.into_record(), expected);
+
+        let entry = LogEntry {
+            severity_text: String::from("warn"),
+            body: json!({"event": "slow"}),
+            ..Default::default()
+        };
+        let expected = LogRecord::new("OTLP", r#"{"event":"slow"}"#).with_level(Level::Warning);
+        assert_eq!(entry.into_record(), expected);
+    }
+}
@@ -0,0 +1,219 @@
+//! Decoder of `ExportLogsServiceRequest` in the JSON encoding of OTLP
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::entry::{Attributes, LogEntry};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Request {
+    #[serde(default)]
+    resource_logs: Vec<ResourceLogs>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceLogs {
+    #[serde(default)]
+    resource: Resource,
+    #[serde(default, alias = "instrumentationLibraryLogs")]
+    scope_logs: Vec<ScopeLogs>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Resource {
+    #[serde(default)]
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScopeLogs {
+    #[serde(default, alias = "instrumentationLibrary")]
+    scope: Scope,
+    #[serde(default)]
+    log_records: Vec<LogRecord>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct Scope {
+    #[serde(default)]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogRecord {
+    /// 64 bit integers are strings in OTLP/JSON, but numbers are accepted too
+    #[serde(default)]
+    time_unix_nano: Value,
+    #[serde(default)]
+    observed_time_unix_nano: Value,
+    #[serde(default)]
+    severity_number: u64,
+    #[serde(default)]
+    severity_text: String,
+    #[serde(default)]
+    body: AnyValue,
+    #[serde(default)]
+    attributes: Vec<KeyValue>,
+    #[serde(default)]
+    trace_id: String,
+    #[serde(default)]
+    span_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValue {
+    key: String,
+    #[serde(default)]
+    value: AnyValue,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct AnyValue {
+    string_value: Option<String>,
+    bool_value: Option<bool>,
+    int_value: Option<Value>,
+    double_value: Option<f64>,
+    array_value: Option<ArrayValue>,
+    kvlist_value: Option<KeyValueList>,
+    bytes_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArrayValue {
+    #[serde(default)]
+    values: Vec<AnyValue>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KeyValueList {
+    #[serde(default)]
+    values: Vec<KeyValue>,
+}
+
+impl AnyValue {
+    fn into_value(self) -> Value {
+        if let Some(text) = self.string_value {
+            Value::String(text)
+        } else if let Some(flag) = self.bool_value {
+            Value::Bool(flag)
+        } else if let Some(number) = self.int_value {
+            integer(&number).map(Value::from).unwrap_or(number)
+        } else if let Some(number) = self.double_value {
+            Value::from(number)
+        } else if let Some(array) = self.array_value {
+            Value::Array(array.values.into_iter().map(AnyValue::into_value).collect())
+        } else if let Some(list) = self.kvlist_value {
+            let map: Map<String, Value> = list
+                .values
+                .into_iter()
+                .map(|kv| (kv.key, kv.value.into_value()))
+                .collect();
+            Value::Object(map)
+        } else if let Some(bytes) = self.bytes_value {
+            Value::String(bytes)
+        } else {
+            Value::Null
+        }
+    }
+}
+
+fn integer(value: &Value) -> Option<i64> {
+    match value {
+        Value::String(text) => text.parse().ok(),
+        value => value.as_i64(),
+    }
+}
+
+fn attributes(key_values: Vec<KeyValue>) -> Attributes {
+    key_values
+        .into_iter()
+        .map(|kv| (kv.key, kv.value.into_value()))
+        .collect()
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<LogEntry>> {
+    let request: Request = serde_json::from_slice(data)?;
+
+    let mut entries = Vec::new();
+    for resource_logs in request.resource_logs {
+        let resource = attributes(resource_logs.resource.attributes);
+
+        for scope_logs in resource_logs.scope_logs {
+            for record in scope_logs.log_records {
+                let entry = LogEntry {
+                    resource: resource.clone(),
+                    scope: scope_logs.scope.name.clone(),
+                    time_unix_nano: integer(&record.time_unix_nano).unwrap_or(0) as u64,
+                    observed_time_unix_nano: integer(&record.observed_time_unix_nano).unwrap_or(0)
+                        as u64,
+                    severity_number: record.severity_number,
+                    severity_text: record.severity_text,
+                    body: record.body.into_value(),
+                    attributes: attributes(record.attributes),
+                    trace_id: record.trace_id.to_lowercase(),
+                    span_id: record.span_id.to_lowercase(),
+                };
+                entries.push(entry);
+            }
+        }
+    }
+
+    Ok(entries)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use serde_json::json;
+
+    use super::{super::entry::LogEntry, decode};
+
+    pub static REQUEST: &str = r#"{
+        "resourceLogs": [{
+            "resource": {
+                "attributes": [{"key": "service.name", "value": {"stringValue": "checkout"}}]
+            },
+            "scopeLogs": [{
+                "scope": {"name": "payments"},
+                "logRecords": [{
+                    "timeUnixNano": "1609556645123000000",
+                    "severityNumber": 17,
+                    "severityText": "ERROR",
+                    "body": {"stringValue": "payment failed"},
+                    "attributes": [
+                        {"key": "order.id", "value": {"intValue": "42"}},
+                        {"key": "tags", "value": {"arrayValue": {"values": [{"stringValue": "eu"}]}}}
+                    ],
+                    "traceId": "5B8EFFF798038103D269B633813FC60C",
+                    "spanId": ""
+                }]
+            }]
+        }]
+    }"#;
+
+    #[test]
+    fn main() {
+        let expected = LogEntry {
+            resource: vec![(String::from("service.name"), json!("checkout"))],
+            scope: String::from("payments"),
+            time_unix_nano: 1_609_556_645_123_000_000,
+            severity_number: 17,
+            severity_text: String::from("ERROR"),
+            body: json!("payment failed"),
+            attributes: vec![
+                (String::from("order.id"), json!(42)),
+                (String::from("tags"), json!(["eu"])),
+            ],
+            trace_id: String::from("5b8efff798038103d269b633813fc60c"),
+            ..Default::default()
+        };
+        assert_eq!(decode(REQUEST.as_bytes()).unwrap(), vec![expected]);
+
+        assert!(decode(b"{}").unwrap().is_empty());
+        assert!(decode(b"not json").is_err());
+    }
+}
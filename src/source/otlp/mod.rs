@@ -0,0 +1,210 @@
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use hyper::{
+    header::{HeaderValue, CONTENT_ENCODING, CONTENT_TYPE},
+    server::{conn::AddrIncoming, Builder},
+    Body, Request, Response, Server, StatusCode,
+};
+use std::{io::Read, sync::Arc};
+
+use crate::source::{
+    webhook::{self, Accepted, Rejection},
+    LogSource, LogSourceStream,
+};
+
+mod config;
+mod entry;
+mod json;
+mod protobuf;
+pub use self::config::OtlpLogSourceConfig;
+
+pub struct OtlpLogSource {
+    builder: Builder<AddrIncoming>,
+    config: Arc<OtlpLogSourceConfig>,
+}
+
+impl OtlpLogSource {
+    pub fn new(config: OtlpLogSourceConfig) -> Result<Self> {
+        let builder = Server::try_bind(&config.addr)?;
+
+        Ok(OtlpLogSource {
+            builder,
+            config: Arc::new(config),
+        })
+    }
+}
+
+impl LogSource for OtlpLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let config = self.config;
+
+        webhook::serve(self.builder, move |req| parse_request(config.clone(), req))
+    }
+}
+
+async fn parse_request(
+    config: Arc<OtlpLogSourceConfig>,
+    req: Request<Body>,
+) -> Result<Accepted, Rejection> {
+    if req.uri().path() != config.path {
+        return Err(webhook::not_found());
+    }
+    webhook::check_request(&req, config.token.as_deref())?;
+
+    let header = |name| {
+        req.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| {
+                value
+                    .split(';')
+                    .next()
+                    .unwrap_or_default()
+                    .trim()
+                    .to_lowercase()
+            })
+            .unwrap_or_default()
+    };
+    let content_type = header(CONTENT_TYPE);
+    let gzipped = header(CONTENT_ENCODING) == "gzip";
+
+    // the response is an empty ExportLogsServiceResponse in the encoding of the request
+    let (decode, content_type, response): (fn(&[u8]) -> Result<_>, _, _) =
+        match content_type.as_str() {
+            "application/x-protobuf" => (protobuf::decode, "application/x-protobuf", ""),
+            "application/json" => (json::decode, "application/json", "{}"),
+            _ => {
+                let text = format!("Unsupported content type \"{}\"", content_type);
+                return Err((StatusCode::UNSUPPORTED_MEDIA_TYPE, text));
+            }
+        };
+
+    let mut bytes = webhook::read_body(req, config.max_body).await?.to_vec();
+    if gzipped {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .take(config.max_body as u64 + 1)
+            .read_to_end(&mut decompressed)
+            .map_err(webhook::bad_request)?;
+        if decompressed.len() > config.max_body {
+            return Err(webhook::too_large(config.max_body));
+        }
+        bytes = decompressed;
+    }
+
+    let records = decode(&bytes)
+        .map_err(webhook::bad_request)?
+        .into_iter()
+        .filter(|entry| entry.level().is_none_or(|level| level <= config.priority))
+        .map(|entry| entry.into_record())
+        .collect();
+
+    let mut response = Response::new(Body::from(response));
+    let content_type = HeaderValue::from_static(content_type);
+    response.headers_mut().insert(CONTENT_TYPE, content_type);
+
+    Ok(Accepted { records, response })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use flate2::{write::GzEncoder, Compression};
+    use futures::StreamExt;
+    use reqwest::{Client, StatusCode};
+    use std::io::Write;
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{json, protobuf, OtlpLogSource, OtlpLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let config = OtlpLogSourceConfig {
+            addr: "127.0.0.1:14318".parse().unwrap(),
+            priority: Level::Warning,
+            ..Default::default()
+        };
+
+        let source = OtlpLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+        let client = Client::new();
+        let url = "http://127.0.0.1:14318/v1/logs";
+
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json; charset=utf-8")
+            .body(json::tests::REQUEST)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["Content-Type"], "application/json");
+        assert_eq!(response.text().await.unwrap(), "{}");
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&protobuf::tests::request()).unwrap();
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/x-protobuf")
+            .header("Content-Encoding", "gzip")
+            .body(encoder.finish().unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["Content-Type"], "application/x-protobuf");
+        assert!(response.bytes().await.unwrap().is_empty());
+
+        // small when compressed, but larger than max_body
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&vec![0; 2 * 1024 * 1024]).unwrap();
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/x-protobuf")
+            .header("Content-Encoding", "gzip")
+            .body(encoder.finish().unwrap())
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = client
+            .post(url)
+            .header("Content-Type", "text/plain")
+            .body("hello")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        // below the priority
+        let info = json::tests::REQUEST.replace("\"severityNumber\": 17", "\"severityNumber\": 9");
+        let response = client
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(info)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let timestamp = Utc.timestamp_millis(1_609_556_645_123);
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("checkout", "payment failed")
+                .with_level(Level::Error)
+                .with_timestamp(timestamp)
+                .with_metadata("order.id", "42")
+                .with_metadata("tags", r#"["eu"]"#)
+                .with_metadata("trace_id", "5b8efff798038103d269b633813fc60c"),
+            LogRecord::new("checkout", "retrying payment")
+                .with_level(Level::Warning)
+                .with_timestamp(timestamp)
+                .with_metadata("attempt", "2")
+                .with_metadata("trace_id", "abcd"),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+}
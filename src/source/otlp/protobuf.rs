@@ -0,0 +1,328 @@
+//! Decoder of `ExportLogsServiceRequest` in the protobuf encoding, reads only
+//! the fields logram needs and skips the rest
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Number, Value};
+use std::str;
+
+use super::entry::{to_hex, Attributes, LogEntry};
+
+/// Maximum nesting of arrays and key-value lists in an attribute or body
+const MAX_DEPTH: usize = 64;
+
+enum Wire<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    Fixed32,
+}
+
+/// Iterates over the fields of a message
+struct Fields<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Fields { data }
+    }
+    fn varint(&mut self) -> Result<u64> {
+        let mut value = 0;
+        for shift in (0..64).step_by(7) {
+            let (&byte, rest) = self
+                .data
+                .split_first()
+                .ok_or_else(|| anyhow!("Truncated varint"))?;
+            self.data = rest;
+
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+
+        Err(anyhow!("Varint is too long"))
+    }
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.data.len() < len {
+            return Err(anyhow!("Truncated field"));
+        }
+
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+    fn next_field(&mut self) -> Result<(u64, Wire<'a>)> {
+        let key = self.varint()?;
+        let wire = match key & 0x07 {
+            0 => Wire::Varint(self.varint()?),
+            1 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(self.take(8)?);
+                Wire::Fixed64(u64::from_le_bytes(bytes))
+            }
+            2 => {
+                let len = self.varint()? as usize;
+                Wire::Bytes(self.take(len)?)
+            }
+            5 => {
+                self.take(4)?;
+                Wire::Fixed32
+            }
+            wire_type => return Err(anyhow!("Unsupported wire type {}", wire_type)),
+        };
+
+        Ok((key >> 3, wire))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u64, Wire<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            None
+        } else {
+            Some(self.next_field())
+        }
+    }
+}
+
+fn string(bytes: &[u8]) -> Result<String> {
+    Ok(str::from_utf8(bytes)?.to_owned())
+}
+
+pub fn decode(data: &[u8]) -> Result<Vec<LogEntry>> {
+    let mut entries = Vec::new();
+
+    for field in Fields::new(data) {
+        if let (1, Wire::Bytes(resource_logs)) = field? {
+            decode_resource_logs(resource_logs, &mut entries)?;
+        }
+    }
+
+    Ok(entries)
+}
+
+fn decode_resource_logs(data: &[u8], entries: &mut Vec<LogEntry>) -> Result<()> {
+    let mut resource = Attributes::new();
+    let mut scope_logs = Vec::new();
+
+    for field in Fields::new(data) {
+        match field? {
+            (1, Wire::Bytes(data)) => {
+                for field in Fields::new(data) {
+                    if let (1, Wire::Bytes(key_value)) = field? {
+                        resource.push(decode_key_value(key_value, 0)?);
+                    }
+                }
+            }
+            // 1000 is the deprecated instrumentation_library_logs with the same layout
+            (2, Wire::Bytes(data)) | (1000, Wire::Bytes(data)) => scope_logs.push(data),
+            _ => {}
+        }
+    }
+
+    for data in scope_logs {
+        let mut scope = String::new();
+        let mut records = Vec::new();
+
+        for field in Fields::new(data) {
+            match field? {
+                (1, Wire::Bytes(data)) => {
+                    for field in Fields::new(data) {
+                        if let (1, Wire::Bytes(name)) = field? {
+                            scope = string(name)?;
+                        }
+                    }
+                }
+                (2, Wire::Bytes(data)) => records.push(data),
+                _ => {}
+            }
+        }
+
+        for data in records {
+            let entry = LogEntry {
+                resource: resource.clone(),
+                scope: scope.clone(),
+                ..decode_log_record(data)?
+            };
+            entries.push(entry);
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_log_record(data: &[u8]) -> Result<LogEntry> {
+    let mut entry = LogEntry::default();
+
+    for field in Fields::new(data) {
+        match field? {
+            (1, Wire::Fixed64(time)) => entry.time_unix_nano = time,
+            (11, Wire::Fixed64(time)) => entry.observed_time_unix_nano = time,
+            (2, Wire::Varint(severity)) => entry.severity_number = severity,
+            (3, Wire::Bytes(text)) => entry.severity_text = string(text)?,
+            (5, Wire::Bytes(body)) => entry.body = decode_any_value(body, 0)?,
+            (6, Wire::Bytes(key_value)) => entry.attributes.push(decode_key_value(key_value, 0)?),
+            (9, Wire::Bytes(id)) => entry.trace_id = to_hex(id),
+            (10, Wire::Bytes(id)) => entry.span_id = to_hex(id),
+            _ => {}
+        }
+    }
+
+    Ok(entry)
+}
+
+fn decode_key_value(data: &[u8], depth: usize) -> Result<(String, Value)> {
+    let mut key = String::new();
+    let mut value = Value::Null;
+
+    for field in Fields::new(data) {
+        match field? {
+            (1, Wire::Bytes(bytes)) => key = string(bytes)?,
+            (2, Wire::Bytes(bytes)) => value = decode_any_value(bytes, depth)?,
+            _ => {}
+        }
+    }
+
+    Ok((key, value))
+}
+
+fn decode_any_value(data: &[u8], depth: usize) -> Result<Value> {
+    if depth > MAX_DEPTH {
+        return Err(anyhow!("Value is nested deeper than {} levels", MAX_DEPTH));
+    }
+
+    let mut value = Value::Null;
+
+    for field in Fields::new(data) {
+        value = match field? {
+            (1, Wire::Bytes(bytes)) => Value::String(string(bytes)?),
+            (2, Wire::Varint(flag)) => Value::Bool(flag != 0),
+            (3, Wire::Varint(number)) => Value::from(number as i64),
+            (4, Wire::Fixed64(bits)) => Number::from_f64(f64::from_bits(bits))
+                .map(Value::Number)
+                .unwrap_or(Value::Null),
+            (5, Wire::Bytes(array)) => {
+                let mut values = Vec::new();
+                for field in Fields::new(array) {
+                    if let (1, Wire::Bytes(item)) = field? {
+                        values.push(decode_any_value(item, depth + 1)?);
+                    }
+                }
+                Value::Array(values)
+            }
+            (6, Wire::Bytes(list)) => {
+                let mut map = Map::new();
+                for field in Fields::new(list) {
+                    if let (1, Wire::Bytes(key_value)) = field? {
+                        let (key, value) = decode_key_value(key_value, depth + 1)?;
+                        map.insert(key, value);
+                    }
+                }
+                Value::Object(map)
+            }
+            (7, Wire::Bytes(bytes)) => Value::String(to_hex(bytes)),
+            _ => continue,
+        };
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use serde_json::json;
+
+    use super::{super::entry::LogEntry, decode};
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        while value >= 0x80 {
+            bytes.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+        bytes
+    }
+
+    fn message(number: u64, data: &[u8]) -> Vec<u8> {
+        let mut bytes = varint(number << 3 | 2);
+        bytes.extend(varint(data.len() as u64));
+        bytes.extend(data);
+        bytes
+    }
+
+    fn key_value(key: &str, value: &[u8]) -> Vec<u8> {
+        [message(1, key.as_bytes()), message(2, value)].concat()
+    }
+
+    /// Request with one record of the "checkout" service
+    pub fn request() -> Vec<u8> {
+        let service = key_value("service.name", &message(1, b"checkout"));
+        let resource = message(1, &message(1, &service));
+
+        let mut record = varint(1 << 3 | 1);
+        record.extend(&1_609_556_645_123_000_000u64.to_le_bytes());
+        record.extend(varint(2 << 3));
+        record.extend(varint(13));
+        record.extend(message(3, b"WARN"));
+        record.extend(message(5, &message(1, b"retrying payment")));
+        let attempt = [varint(3 << 3), varint(2)].concat();
+        record.extend(message(6, &key_value("attempt", &attempt)));
+        record.extend(message(9, &[0xab, 0xcd]));
+        // unknown field
+        record.extend(message(100, b"ignored"));
+
+        let scope = message(1, &message(1, b"payments"));
+        let scope_logs = [scope, message(2, &record)].concat();
+        let resource_logs = [resource, message(2, &scope_logs)].concat();
+
+        message(1, &resource_logs)
+    }
+
+    #[test]
+    fn main() {
+        let expected = LogEntry {
+            resource: vec![(String::from("service.name"), json!("checkout"))],
+            scope: String::from("payments"),
+            time_unix_nano: 1_609_556_645_123_000_000,
+            severity_number: 13,
+            severity_text: String::from("WARN"),
+            body: json!("retrying payment"),
+            attributes: vec![(String::from("attempt"), json!(2))],
+            trace_id: String::from("abcd"),
+            ..Default::default()
+        };
+        assert_eq!(decode(&request()).unwrap(), vec![expected]);
+
+        let list = [
+            message(1, &key_value("a", &message(1, b"x"))),
+            message(1, &key_value("b", &[varint(2 << 3), varint(1)].concat())),
+        ]
+        .concat();
+        let body = message(5, &message(6, &list));
+        let request = message(1, &message(2, &message(2, &body)));
+        let entries = decode(&request).unwrap();
+        assert_eq!(entries[0].body, json!({"a": "x", "b": true}));
+
+        assert!(decode(&[0x0a, 0x05, 0x01]).is_err());
+    }
+
+    #[test]
+    fn nesting() {
+        let nested = |depth: usize| {
+            let mut value = message(1, b"x");
+            for _ in 0..depth {
+                value = message(5, &message(1, &value));
+            }
+            message(1, &message(2, &message(2, &message(5, &value))))
+        };
+
+        let entries = decode(&nested(64)).unwrap();
+        assert!(entries[0].body.is_array());
+
+        let error = decode(&nested(10_000)).unwrap_err();
+        assert_eq!(error.to_string(), "Value is nested deeper than 64 levels");
+    }
+}
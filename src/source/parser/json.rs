@@ -0,0 +1,66 @@
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+/// Parses a JSON object, nested objects are flattened into dotted keys
+pub fn parse(line: &str) -> Option<BTreeMap<String, String>> {
+    let object = match serde_json::from_str(line) {
+        Ok(Value::Object(object)) => object,
+        _ => return None,
+    };
+
+    let mut fields = BTreeMap::new();
+    flatten(&mut fields, None, object);
+
+    Some(fields)
+}
+
+fn flatten(
+    fields: &mut BTreeMap<String, String>,
+    prefix: Option<&str>,
+    object: Map<String, Value>,
+) {
+    for (key, value) in object {
+        let key = match prefix {
+            Some(prefix) => format!("{}.{}", prefix, key),
+            None => key,
+        };
+
+        match value {
+            Value::Object(object) => flatten(fields, Some(&key), object),
+            Value::String(value) => {
+                fields.insert(key, value);
+            }
+            Value::Null => {}
+            value => {
+                fields.insert(key, value.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn main() {
+        let line = r#"{"level":"error","msg":"request failed","ts":1609556645.5,"http":{"status":502,"path":"/api"},"tags":["a","b"],"trace":null}"#;
+        let fields: Vec<(String, String)> = parse(line).unwrap().into_iter().collect();
+        let expected = vec![
+            ("http.path", "/api"),
+            ("http.status", "502"),
+            ("level", "error"),
+            ("msg", "request failed"),
+            ("tags", r#"["a","b"]"#),
+            ("ts", "1609556645.5"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+
+        assert_eq!(fields, expected);
+        assert_eq!(parse("plain text"), None);
+        assert_eq!(parse("[1, 2]"), None);
+    }
+}
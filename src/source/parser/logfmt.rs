@@ -0,0 +1,119 @@
+use std::{collections::BTreeMap, iter::Peekable, str::Chars};
+
+/// Parses `key=value key="quoted value" flag` pairs, a bare key has the value "true".
+/// A line without any `key=value` pair is not considered logfmt.
+pub fn parse(line: &str) -> Option<BTreeMap<String, String>> {
+    let mut fields = BTreeMap::new();
+    let mut has_pair = false;
+    let mut chars = line.chars().peekable();
+
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let key = read_key(&mut chars);
+        if key.is_empty() {
+            return None;
+        }
+
+        let value = match chars.peek() {
+            Some('=') => {
+                chars.next();
+                has_pair = true;
+                read_value(&mut chars)?
+            }
+            Some(c) if !c.is_whitespace() => return None,
+            _ => String::from("true"),
+        };
+
+        fields.insert(key, value);
+    }
+
+    if has_pair {
+        Some(fields)
+    } else {
+        None
+    }
+}
+
+fn read_key(chars: &mut Peekable<Chars>) -> String {
+    let mut key = String::new();
+
+    while let Some(&c) = chars.peek() {
+        if !(c.is_alphanumeric() || c == '_' || c == '.' || c == '-') {
+            break;
+        }
+
+        key.push(c);
+        chars.next();
+    }
+
+    key
+}
+
+fn read_value(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut value = String::new();
+
+    if chars.peek() != Some(&'"') {
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                break;
+            }
+
+            value.push(c);
+            chars.next();
+        }
+
+        return Some(value);
+    }
+
+    chars.next();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                't' => value.push('\t'),
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse;
+
+    #[test]
+    fn main() {
+        let line = r#"ts=2021-01-02T03:04:05Z level=warn msg="disk \"/data\" is almost full" used=95% dry_run"#;
+        let fields: Vec<(String, String)> = parse(line).unwrap().into_iter().collect();
+        let expected = vec![
+            ("dry_run", "true"),
+            ("level", "warn"),
+            ("msg", r#"disk "/data" is almost full"#),
+            ("ts", "2021-01-02T03:04:05Z"),
+            ("used", "95%"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+
+        assert_eq!(fields, expected);
+        assert_eq!(parse("empty= value=\"\"").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(parse("plain text line"), None);
+        assert_eq!(parse("error: something=failed"), None);
+        assert_eq!(parse(r#"msg="unterminated"#), None);
+        assert_eq!(parse(""), None);
+    }
+}
@@ -0,0 +1,452 @@
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+
+use crate::source::{timestamp::bsd_timestamp_at, LogRecord};
+
+mod json;
+mod logfmt;
+mod pattern;
+pub use self::pattern::Preset;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Text,
+    Json,
+    Logfmt,
+    Regex,
+}
+
+/// Names of the parsed fields which fill the record, the rest become metadata.
+/// Message and title may have several alternative names, the first present one is used
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct FieldMapping {
+    pub level: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub message: Vec<String>,
+    pub timestamp: String,
+    #[serde(deserialize_with = "one_or_many")]
+    pub title: Vec<String>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        FieldMapping {
+            level: String::from("level"),
+            message: vec![
+                String::from("message"),
+                String::from("body"),
+                String::from("msg"),
+            ],
+            timestamp: String::from("ts"),
+            title: vec![String::from("title")],
+        }
+    }
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(name) => vec![name],
+        OneOrMany::Many(names) => names,
+    })
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(default)]
+pub struct ParserConfig {
+    pub format: Format,
+    pub fields: FieldMapping,
+    pub pattern: Option<String>,
+    pub preset: Option<Preset>,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        ParserConfig {
+            format: Format::Text,
+            fields: FieldMapping::default(),
+            pattern: None,
+            preset: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Parser {
+    format: Format,
+    fields: FieldMapping,
+    regex: Option<Regex>,
+}
+
+impl Parser {
+    pub fn new(config: ParserConfig) -> Result<Self> {
+        let regex = match (config.format, config.pattern, config.preset) {
+            (Format::Regex, Some(pattern), None) => Some(Regex::new(&pattern)?),
+            (Format::Regex, None, Some(preset)) => Some(Regex::new(preset.pattern())?),
+            (Format::Regex, _, _) => {
+                return Err(anyhow!("Regex format requires either pattern or preset"))
+            }
+            _ => None,
+        };
+
+        Ok(Parser {
+            format: config.format,
+            fields: config.fields,
+            regex,
+        })
+    }
+    /// Parses the whole body of the record as one message
+    #[cfg(any(
+        feature = "ls_pipe",
+        feature = "ls_stdin",
+        feature = "ls_containerd",
+        feature = "ls_kubernetes",
+        test
+    ))]
+    pub fn parse_record(&self, record: LogRecord) -> LogRecord {
+        match self.parse_fields(&record.body) {
+            Some(fields) => self.apply(record, fields),
+            None => record,
+        }
+    }
+    /// Parses each line of the record body as a separate message,
+    /// consecutive lines which don't parse are kept together as raw text
+    #[cfg(any(feature = "ls_filesystem", feature = "ls_docker", test))]
+    pub fn parse_lines(&self, record: LogRecord) -> Vec<LogRecord> {
+        if self.format == Format::Text || record.body.is_empty() {
+            return vec![record];
+        }
+
+        let mut records = Vec::new();
+        let mut raw_lines: Vec<&str> = Vec::new();
+
+        for line in record.body.lines() {
+            match self.parse_fields(line) {
+                Some(fields) => {
+                    if !raw_lines.is_empty() {
+                        records.push(with_body(&record, raw_lines.join("\n")));
+                        raw_lines.clear();
+                    }
+                    records.push(self.apply(with_body(&record, line.to_owned()), fields));
+                }
+                None => raw_lines.push(line),
+            }
+        }
+        if !raw_lines.is_empty() {
+            records.push(with_body(&record, raw_lines.join("\n")));
+        }
+
+        records
+    }
+    fn parse_fields(&self, text: &str) -> Option<BTreeMap<String, String>> {
+        match self.format {
+            Format::Text => None,
+            Format::Json => json::parse(text.trim()),
+            Format::Logfmt => logfmt::parse(text),
+            Format::Regex => pattern::parse(self.regex.as_ref()?, text),
+        }
+    }
+    fn apply(&self, mut record: LogRecord, mut fields: BTreeMap<String, String>) -> LogRecord {
+        if let Some(title) = take_first(&mut fields, &self.fields.title) {
+            record.title = title;
+        }
+        if let Some(message) = take_first(&mut fields, &self.fields.message) {
+            record.body = message;
+        }
+        if let Some(level) = fields
+            .get(&self.fields.level)
+            .and_then(|level| level.parse().ok())
+        {
+            fields.remove(&self.fields.level);
+            record.level = Some(level);
+        }
+        if let Some(timestamp) = fields
+            .get(&self.fields.timestamp)
+            .and_then(|ts| parse_timestamp(ts))
+        {
+            fields.remove(&self.fields.timestamp);
+            record.timestamp = Some(timestamp);
+        }
+
+        record.metadata.extend(fields);
+        record
+    }
+}
+
+fn take_first(fields: &mut BTreeMap<String, String>, keys: &[String]) -> Option<String> {
+    keys.iter().find_map(|key| fields.remove(key))
+}
+
+#[cfg(any(feature = "ls_filesystem", feature = "ls_docker", test))]
+fn with_body(record: &LogRecord, body: String) -> LogRecord {
+    LogRecord {
+        body,
+        ..record.clone()
+    }
+}
+
+/// Parses RFC3339, common log format, unix timestamps in seconds or milliseconds
+/// and some formats without timezone (as UTC)
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    parse_timestamp_at(value, Utc::now())
+}
+
+/// Parses the timestamp, the year of syslog timestamps is guessed from `now`
+fn parse_timestamp_at(value: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Some(time.with_timezone(&Utc));
+    }
+    if let Ok(time) = DateTime::parse_from_str(value, "%d/%b/%Y:%H:%M:%S %z") {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    // PostgreSQL time with the zone abbreviation or a numeric offset, other zones are ambiguous
+    for zone in &[" UTC", " GMT"] {
+        if let Some(value) = value.strip_suffix(zone) {
+            if let Ok(time) = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f") {
+                return Some(Utc.from_utc_datetime(&time));
+            }
+        }
+    }
+    if let Ok(time) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f %#z") {
+        return Some(time.with_timezone(&Utc));
+    }
+
+    let naive_formats = ["%Y-%m-%d %H:%M:%S%.f", "%a %b %d %H:%M:%S%.f %Y"];
+    for format in &naive_formats {
+        if let Ok(time) = NaiveDateTime::parse_from_str(value, format) {
+            return Some(Utc.from_utc_datetime(&time));
+        }
+    }
+
+    if let Some(time) = bsd_timestamp_at(value, now) {
+        return Some(time);
+    }
+
+    let number: f64 = value.parse().ok()?;
+    let millis = if number.abs() >= 1e11 {
+        number
+    } else {
+        number * 1000.0
+    };
+
+    Utc.timestamp_millis_opt(millis as i64).single()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::source::{Level, LogRecord};
+
+    use super::{
+        parse_timestamp, parse_timestamp_at, FieldMapping, Format, Parser, ParserConfig, Preset,
+    };
+
+    #[test]
+    fn main() {
+        let parser = Parser::new(ParserConfig {
+            format: Format::Json,
+            fields: FieldMapping {
+                title: vec![String::from("service")],
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+
+        let body = [
+            r#"{"level":"warn","msg":"slow query","ts":1609556645123,"service":"db","ms":1500}"#,
+            "panic: runtime error",
+            "goroutine 1 [running]:",
+            r#"{"msg":"restarted","ts":"2021-01-02T03:04:06Z"}"#,
+        ];
+        let record = LogRecord::new("app.log", body.join("\n")).with_metadata("stream", "stdout");
+
+        let expected = vec![
+            LogRecord::new("db", "slow query")
+                .with_level(Level::Warning)
+                .with_timestamp(Utc.timestamp_millis(1_609_556_645_123))
+                .with_metadata("ms", "1500")
+                .with_metadata("stream", "stdout"),
+            LogRecord::new("app.log", "panic: runtime error\ngoroutine 1 [running]:")
+                .with_metadata("stream", "stdout"),
+            LogRecord::new("app.log", "restarted")
+                .with_timestamp(Utc.ymd(2021, 1, 2).and_hms(3, 4, 6))
+                .with_metadata("stream", "stdout"),
+        ];
+
+        assert_eq!(parser.parse_lines(record), expected);
+    }
+
+    #[test]
+    fn logfmt() {
+        let parser = Parser::new(ParserConfig {
+            format: Format::Logfmt,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let record = LogRecord::new("app", r#"level=error msg="payment failed" order=42"#);
+        let expected = LogRecord::new("app", "payment failed")
+            .with_level(Level::Error)
+            .with_metadata("order", "42");
+
+        assert_eq!(parser.parse_record(record), expected);
+    }
+
+    #[test]
+    fn regex() {
+        let parser = Parser::new(ParserConfig {
+            format: Format::Regex,
+            pattern: Some(String::from(
+                r"^(?P<level>[A-Z]+) \[(?P<module>\w+)\] (?P<msg>.*)$",
+            )),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let record = LogRecord::new("app", "WARN [cache] eviction is slow");
+        let expected = LogRecord::new("app", "eviction is slow")
+            .with_level(Level::Warning)
+            .with_metadata("module", "cache");
+        assert_eq!(parser.parse_record(record), expected);
+
+        let record = LogRecord::new("app", "unstructured");
+        assert_eq!(parser.parse_record(record.clone()), record);
+
+        let config = ParserConfig {
+            format: Format::Regex,
+            ..Default::default()
+        };
+        assert!(Parser::new(config).is_err());
+    }
+
+    #[test]
+    fn preset() {
+        let parser = Parser::new(ParserConfig {
+            format: Format::Regex,
+            preset: Some(Preset::Apache),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let line =
+            "[Sat Jan 02 03:04:05.5 2021] [core:crit] [pid 42] AH00046: child process exited";
+        let expected = LogRecord::new("error.log", "AH00046: child process exited")
+            .with_level(Level::Critical)
+            .with_timestamp(Utc.ymd(2021, 1, 2).and_hms_milli(3, 4, 5, 500))
+            .with_metadata("module", "core")
+            .with_metadata("pid", "42");
+
+        let record = LogRecord::new("error.log", line);
+        assert_eq!(parser.parse_record(record), expected);
+
+        let line = "[Sat Jan 02 03:04:05.5 2021] [rewrite:trace3] [pid 42] applying pattern";
+        let record = parser.parse_record(LogRecord::new("error.log", line));
+        assert_eq!(record.level, Some(Level::Debug));
+
+        let parser = Parser::new(ParserConfig {
+            format: Format::Regex,
+            preset: Some(Preset::Postgres),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let line = "2021-01-02 03:04:05.5 UTC [1234] LOG:  checkpoint starting: time";
+        let expected = LogRecord::new("postgresql.log", "checkpoint starting: time")
+            .with_level(Level::Info)
+            .with_timestamp(Utc.ymd(2021, 1, 2).and_hms_milli(3, 4, 5, 500))
+            .with_metadata("pid", "1234")
+            .with_metadata("timezone", "UTC");
+        let record = LogRecord::new("postgresql.log", line);
+        assert_eq!(parser.parse_record(record), expected);
+
+        let line = "2021-01-02 04:04:05.5 CET [1234] DEBUG2:  autovacuum: processing database";
+        let record = parser.parse_record(LogRecord::new("postgresql.log", line));
+        assert_eq!(record.level, Some(Level::Debug));
+        assert_eq!(record.timestamp, None);
+        assert_eq!(
+            record.metadata.get("ts").map(String::as_str),
+            Some("2021-01-02 04:04:05.5 CET")
+        );
+    }
+
+    #[test]
+    fn timestamps() {
+        let expected = Some(Utc.ymd(2021, 1, 2).and_hms(3, 4, 5));
+
+        assert_eq!(parse_timestamp("2021-01-02T06:04:05+03:00"), expected);
+        assert_eq!(parse_timestamp("02/Jan/2021:03:04:05 +0000"), expected);
+        assert_eq!(parse_timestamp("2021-01-02 03:04:05"), expected);
+        assert_eq!(parse_timestamp("Sat Jan 02 03:04:05 2021"), expected);
+        assert_eq!(parse_timestamp("2021-01-02 03:04:05 UTC"), expected);
+        assert_eq!(parse_timestamp("2021-01-02 06:04:05 +03"), expected);
+        assert_eq!(parse_timestamp("2021-01-02 08:34:05 +05:30"), expected);
+        assert_eq!(parse_timestamp("2021-01-02 04:04:05 CET"), None);
+        assert_eq!(parse_timestamp("1609556645"), expected);
+        assert_eq!(parse_timestamp("1609556645000"), expected);
+
+        let syslog = parse_timestamp("Jan  2 03:04:05").unwrap();
+        assert_eq!(
+            syslog.format("%m-%d %H:%M:%S").to_string(),
+            "01-02 03:04:05"
+        );
+        // the previous year's messages read just after New Year
+        let new_year = Utc.ymd(2021, 1, 1).and_hms(0, 0, 30);
+        assert_eq!(
+            parse_timestamp_at("Dec 31 23:59:58", new_year),
+            Some(Utc.ymd(2020, 12, 31).and_hms(23, 59, 58))
+        );
+        assert_eq!(
+            parse_timestamp_at("Jan  1 00:00:29", new_year),
+            Some(Utc.ymd(2021, 1, 1).and_hms(0, 0, 29))
+        );
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+
+    #[test]
+    fn text() {
+        let parser = Parser::new(ParserConfig::default()).unwrap();
+        let record = LogRecord::new("app.log", r#"{"msg":"kept as is"}"#);
+
+        assert_eq!(parser.parse_lines(record.clone()), vec![record]);
+    }
+
+    #[test]
+    fn aliases() {
+        let parser = Parser::new(ParserConfig {
+            format: Format::Json,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let record = LogRecord::new(
+            "app",
+            r#"{"title":"CI","body":"build failed","level":"error"}"#,
+        );
+        let expected = LogRecord::new("CI", "build failed").with_level(Level::Error);
+        assert_eq!(parser.parse_record(record), expected);
+
+        let record = LogRecord::new("app", r#"{"message":"started","msg":"ignored"}"#);
+        let expected = LogRecord::new("app", "started").with_metadata("msg", "ignored");
+        assert_eq!(parser.parse_record(record), expected);
+
+        let config: ParserConfig =
+            serde_yaml::from_str("format: json\nfields:\n  message: text\n  title: [service, app]")
+                .unwrap();
+        assert_eq!(config.fields.message, vec!["text"]);
+        assert_eq!(config.fields.title, vec!["service", "app"]);
+    }
+}
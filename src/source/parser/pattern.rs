@@ -0,0 +1,165 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Built-in patterns for well-known log formats, capture names follow the default field mapping
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    Nginx,
+    Apache,
+    Postgres,
+    Sshd,
+}
+
+impl Preset {
+    pub fn pattern(self) -> &'static str {
+        match self {
+            // combined access log format, which is also used by Apache
+            Preset::Nginx => concat!(
+                r#"^(?P<remote_addr>\S+) \S+ (?P<remote_user>\S+) \[(?P<ts>[^\]]+)\] "#,
+                r#""(?P<msg>(?P<method>[A-Z]+) (?P<path>\S+)[^"]*|[^"]*)" (?P<status>\d{3}) "#,
+                r#"(?P<body_bytes_sent>\d+|-)(?: "(?P<http_referer>[^"]*)" "(?P<http_user_agent>[^"]*)")?"#,
+            ),
+            Preset::Apache => concat!(
+                r"^\[(?P<ts>[^\]]+)\] \[(?:(?P<module>[^:\]]+):)?(?P<level>[^\]]+)\] ",
+                r"(?:\[pid (?P<pid>\d+)(?::tid (?P<tid>\d+))?\] )?",
+                r"(?:\[client (?P<client>[^\]]+)\] )?(?P<msg>.*)$",
+            ),
+            Preset::Postgres => concat!(
+                // the zone is kept in the timestamp, so times in zones other than UTC
+                // or a numeric offset are not mistaken for UTC
+                r"^(?P<ts>\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:\.\d+)?",
+                r"(?: (?P<timezone>[A-Z]+|[+-]\d{2}(?::?\d{2})?))?) ",
+                r"\[(?P<pid>\d+)\](?: \S+)? (?P<level>[A-Z]+\d?):\s+(?P<msg>.*)$",
+            ),
+            Preset::Sshd => concat!(
+                r"^(?P<ts>[A-Z][a-z]{2} +\d+ \d{2}:\d{2}:\d{2}) (?P<host>\S+) sshd(?:-session)?\[(?P<pid>\d+)\]: ",
+                r"(?P<msg>(?:(?P<event>Accepted|Failed) (?P<method>\S+) for (?:invalid user )?",
+                r"(?P<user>\S+) from (?P<ip>\S+) port (?P<port>\d+))?.*)$",
+            ),
+        }
+    }
+}
+
+/// Parses the line with named capture groups of the regex, unmatched groups are skipped
+pub fn parse(regex: &Regex, line: &str) -> Option<BTreeMap<String, String>> {
+    let captures = regex.captures(line)?;
+    let fields = regex
+        .capture_names()
+        .flatten()
+        .filter_map(|name| {
+            let value = captures.name(name)?.as_str();
+            Some((name.to_owned(), value.to_owned()))
+        })
+        .collect();
+
+    Some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+    use std::collections::BTreeMap;
+
+    use super::{parse, Preset};
+
+    fn parse_preset(preset: Preset, line: &str) -> BTreeMap<String, String> {
+        let regex = Regex::new(preset.pattern()).unwrap();
+        parse(&regex, line).unwrap()
+    }
+
+    fn assert_fields(fields: &BTreeMap<String, String>, expected: &[(&str, &str)]) {
+        for (key, value) in expected {
+            assert_eq!(
+                fields.get(*key).map(String::as_str),
+                Some(*value),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn nginx() {
+        let line = r#"10.0.0.1 - alice [02/Jan/2021:03:04:05 +0000] "GET /api/users?id=1 HTTP/1.1" 502 157 "-" "curl/7.68.0""#;
+        let fields = parse_preset(Preset::Nginx, line);
+
+        assert_fields(
+            &fields,
+            &[
+                ("remote_addr", "10.0.0.1"),
+                ("remote_user", "alice"),
+                ("ts", "02/Jan/2021:03:04:05 +0000"),
+                ("msg", "GET /api/users?id=1 HTTP/1.1"),
+                ("method", "GET"),
+                ("path", "/api/users?id=1"),
+                ("status", "502"),
+                ("http_user_agent", "curl/7.68.0"),
+            ],
+        );
+    }
+
+    #[test]
+    fn apache() {
+        let line = "[Sat Jan 02 03:04:05.123456 2021] [proxy:error] [pid 1234:tid 5678] \
+                    [client 10.0.0.1:51234] AH00957: connection refused";
+        let fields = parse_preset(Preset::Apache, line);
+
+        assert_fields(
+            &fields,
+            &[
+                ("ts", "Sat Jan 02 03:04:05.123456 2021"),
+                ("module", "proxy"),
+                ("level", "error"),
+                ("pid", "1234"),
+                ("client", "10.0.0.1:51234"),
+                ("msg", "AH00957: connection refused"),
+            ],
+        );
+    }
+
+    #[test]
+    fn postgres() {
+        let line = "2021-01-02 03:04:05.123 UTC [1234] ERROR:  relation \"users\" does not exist";
+        let fields = parse_preset(Preset::Postgres, line);
+
+        assert_fields(
+            &fields,
+            &[
+                ("ts", "2021-01-02 03:04:05.123 UTC"),
+                ("timezone", "UTC"),
+                ("pid", "1234"),
+                ("level", "ERROR"),
+                ("msg", "relation \"users\" does not exist"),
+            ],
+        );
+    }
+
+    #[test]
+    fn sshd() {
+        let line = "Jan  2 03:04:05 web sshd[1234]: Failed password for invalid user admin \
+                    from 203.0.113.7 port 52214 ssh2";
+        let fields = parse_preset(Preset::Sshd, line);
+
+        assert_fields(
+            &fields,
+            &[
+                ("ts", "Jan  2 03:04:05"),
+                ("host", "web"),
+                ("event", "Failed"),
+                ("method", "password"),
+                ("user", "admin"),
+                ("ip", "203.0.113.7"),
+            ],
+        );
+
+        let line = "Jan  2 03:04:06 web sshd[1234]: Connection closed by 203.0.113.7 port 52214";
+        let fields = parse_preset(Preset::Sshd, line);
+        assert_eq!(fields.get("event"), None);
+        assert_eq!(
+            fields.get("msg").map(String::as_str),
+            Some("Connection closed by 203.0.113.7 port 52214")
+        );
+    }
+}
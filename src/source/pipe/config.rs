@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::source::ParserConfig;
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Kind {
+    Fifo,
+    Socket,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Multiline {
+    pub start: String,
+    #[serde(default = "Multiline::default_timeout")]
+    pub timeout: u64,
+}
+
+impl Multiline {
+    fn default_timeout() -> u64 {
+        500
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub kind: Kind,
+    pub title: Option<String>,
+    pub multiline: Option<Multiline>,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub struct PipeLogSourceConfig {
+    pub entries: Vec<Entry>,
+}
@@ -0,0 +1,374 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    executor,
+    stream::{self, Stream},
+    SinkExt, StreamExt,
+};
+use regex::Regex;
+use std::{
+    ffi::CString,
+    fs::{self, File},
+    io::{self, BufRead, BufReader as StdBufReader},
+    os::unix::{ffi::OsStrExt, fs::FileTypeExt, net as unix_net},
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, BufReader},
+    net::{UnixListener, UnixStream},
+    time,
+};
+
+use crate::source::{LogRecord, LogSource, LogSourceStream, Parser};
+
+mod config;
+mod multiline;
+pub use self::config::PipeLogSourceConfig;
+use self::config::{Entry, Kind};
+use self::multiline::Grouper;
+
+type RecordSender = Sender<Result<LogRecord>>;
+type LineStream = Pin<Box<dyn Stream<Item = io::Result<String>> + Send>>;
+
+struct Target {
+    title: String,
+    parser: Parser,
+    start: Option<Regex>,
+    timeout: Duration,
+}
+
+impl Target {
+    fn new(entry: &Entry) -> Result<Self> {
+        let title = entry
+            .title
+            .clone()
+            .unwrap_or_else(|| entry.path.display().to_string());
+        let (start, timeout) = match &entry.multiline {
+            Some(multiline) => (
+                Some(Regex::new(&multiline.start)?),
+                Duration::from_millis(multiline.timeout),
+            ),
+            None => (None, Duration::from_millis(0)),
+        };
+
+        Ok(Target {
+            title,
+            parser: Parser::new(entry.parser.clone())?,
+            start,
+            timeout,
+        })
+    }
+    fn to_record(&self, text: String) -> LogRecord {
+        self.parser
+            .parse_record(LogRecord::new(self.title.clone(), text))
+    }
+}
+
+pub struct PipeLogSource {
+    fifos: Vec<(PathBuf, Target)>,
+    sockets: Vec<(UnixListener, Target)>,
+}
+
+impl PipeLogSource {
+    pub fn new(config: PipeLogSourceConfig) -> Result<Self> {
+        let mut fifos = Vec::new();
+        let mut sockets = Vec::new();
+
+        for entry in config.entries {
+            let target = Target::new(&entry)?;
+
+            match entry.kind {
+                Kind::Fifo => {
+                    create_fifo(&entry.path)?;
+                    fifos.push((entry.path, target));
+                }
+                Kind::Socket => {
+                    remove_socket(&entry.path)?;
+
+                    let listener = unix_net::UnixListener::bind(&entry.path)?;
+                    sockets.push((UnixListener::from_std(listener)?, target));
+                }
+            }
+        }
+
+        Ok(PipeLogSource { fifos, sockets })
+    }
+}
+
+impl LogSource for PipeLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        for (path, target) in self.fifos {
+            let lines = read_fifo(path);
+            tokio::spawn(group_lines(lines, Arc::new(target), tx.clone()));
+        }
+        for (listener, target) in self.sockets {
+            tokio::spawn(listen_socket(listener, Arc::new(target), tx.clone()));
+        }
+
+        Box::pin(rx)
+    }
+}
+
+/// Removes the socket left by the previous run, any other file at the path is kept
+fn remove_socket(path: &Path) -> Result<()> {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error.into()),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(anyhow!("{} exists and is not a socket", path.display()));
+    }
+
+    fs::remove_file(path)?;
+    Ok(())
+}
+
+fn create_fifo(path: &Path) -> Result<()> {
+    if path.exists() {
+        return if fs::metadata(path)?.file_type().is_fifo() {
+            Ok(())
+        } else {
+            Err(anyhow!("{} exists and is not a FIFO", path.display()))
+        };
+    }
+
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    if unsafe { libc::mkfifo(c_path.as_ptr(), 0o620) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    Ok(())
+}
+
+fn decode_line(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes)
+        .trim_end_matches(&['\r', '\n'][..])
+        .to_owned()
+}
+
+/// Reads the FIFO in a separate thread, reopening it when all writers disconnect
+fn read_fifo(path: PathBuf) -> LineStream {
+    let (mut tx, rx) = futures_mpsc::channel(10);
+
+    thread::spawn(move || loop {
+        // blocks until a writer opens the FIFO
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) => {
+                let _ = executor::block_on(tx.send(Err(error)));
+                break;
+            }
+        };
+        let mut reader = StdBufReader::new(file);
+
+        loop {
+            let mut buffer = Vec::new();
+            let line = match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => break,
+                Ok(_) => Ok(decode_line(&buffer)),
+                Err(error) => Err(error),
+            };
+
+            if executor::block_on(tx.send(line)).is_err() {
+                return;
+            }
+        }
+    });
+
+    Box::pin(rx)
+}
+
+fn read_socket(socket: UnixStream) -> LineStream {
+    let lines = stream::unfold(Some(BufReader::new(socket)), |reader| async move {
+        let mut reader = reader?;
+        let mut buffer = Vec::new();
+
+        match reader.read_until(b'\n', &mut buffer).await {
+            Ok(0) => None,
+            Ok(_) => Some((Ok(decode_line(&buffer)), Some(reader))),
+            Err(error) => Some((Err(error), None)),
+        }
+    });
+
+    Box::pin(lines)
+}
+
+async fn listen_socket(mut listener: UnixListener, target: Arc<Target>, mut tx: RecordSender) {
+    loop {
+        match listener.accept().await {
+            Ok((socket, _)) => {
+                let lines = read_socket(socket);
+                tokio::spawn(group_lines(lines, target.clone(), tx.clone()));
+            }
+            Err(error) => {
+                if tx.send(Err(error.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+async fn group_lines(mut lines: LineStream, target: Arc<Target>, mut tx: RecordSender) {
+    let mut grouper = Grouper::new(target.start.clone());
+
+    loop {
+        let line = if grouper.is_empty() {
+            lines.next().await
+        } else {
+            match time::timeout(target.timeout, lines.next()).await {
+                Ok(line) => line,
+                // no lines for a while, the pending group is complete
+                Err(_) => {
+                    let record = grouper.flush().map(|text| target.to_record(text));
+                    if let Some(record) = record {
+                        if tx.send(Ok(record)).await.is_err() {
+                            return;
+                        }
+                    }
+                    continue;
+                }
+            }
+        };
+
+        let result = match line {
+            Some(Ok(line)) if line.is_empty() && grouper.is_empty() => continue,
+            Some(Ok(line)) => match grouper.push(line) {
+                Some(text) => Ok(target.to_record(text)),
+                None => continue,
+            },
+            Some(Err(error)) => Err(error.into()),
+            None => break,
+        };
+
+        if tx.send(result).await.is_err() {
+            return;
+        }
+    }
+
+    if let Some(text) = grouper.flush() {
+        let _ = tx.send(Ok(target.to_record(text))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+        os::unix::net::UnixStream,
+        thread,
+    };
+
+    use crate::source::{parser::Format, Level, LogRecord, LogSource, ParserConfig};
+
+    use super::{
+        config::{Entry, Kind, Multiline},
+        PipeLogSource, PipeLogSourceConfig,
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let fifo_path = env::temp_dir().join("logram_test_pipe.fifo");
+        let socket_path = env::temp_dir().join("logram_test_pipe.sock");
+        if fifo_path.exists() {
+            fs::remove_file(&fifo_path).unwrap();
+        }
+
+        let config = PipeLogSourceConfig {
+            entries: vec![
+                Entry {
+                    path: fifo_path.clone(),
+                    kind: Kind::Fifo,
+                    title: Some(String::from("fifo")),
+                    multiline: Some(Multiline {
+                        start: String::from(r"^\S"),
+                        timeout: 100,
+                    }),
+                    parser: ParserConfig::default(),
+                },
+                Entry {
+                    path: socket_path.clone(),
+                    kind: Kind::Socket,
+                    title: None,
+                    multiline: None,
+                    parser: ParserConfig {
+                        format: Format::Json,
+                        ..Default::default()
+                    },
+                },
+            ],
+        };
+
+        let source = PipeLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let write_fifo = |text: &'static str| {
+            let path = fifo_path.clone();
+            thread::spawn(move || {
+                let mut fifo = OpenOptions::new().write(true).open(path).unwrap();
+                fifo.write_all(text.as_bytes()).unwrap();
+            });
+        };
+
+        write_fifo("first\n  continued\n\nsecond\n");
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record, LogRecord::new("fifo", "first\n  continued"));
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record, LogRecord::new("fifo", "second"));
+
+        // the FIFO is reopened for the next writer
+        write_fifo("third\n");
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record, LogRecord::new("fifo", "third"));
+
+        let mut socket = UnixStream::connect(&socket_path).unwrap();
+        socket
+            .write_all(b"{\"msg\":\"disk full\",\"level\":\"error\",\"code\":28}\nnot json\n")
+            .unwrap();
+        drop(socket);
+
+        let title = socket_path.display().to_string();
+        let expected = vec![
+            LogRecord::new(title.clone(), "disk full")
+                .with_level(Level::Error)
+                .with_metadata("code", "28"),
+            LogRecord::new(title, "not json"),
+        ];
+        let actual: Vec<LogRecord> = stream.take(2).map(Result::unwrap).collect().await;
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn not_socket() {
+        let path = env::temp_dir().join("logram_test_pipe_not_socket");
+        fs::write(&path, "data").unwrap();
+
+        let config = PipeLogSourceConfig {
+            entries: vec![Entry {
+                path: path.clone(),
+                kind: Kind::Socket,
+                title: None,
+                multiline: None,
+                parser: ParserConfig::default(),
+            }],
+        };
+        let error = PipeLogSource::new(config).err().unwrap();
+
+        assert_eq!(
+            error.to_string(),
+            format!("{} exists and is not a socket", path.display())
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "data");
+        fs::remove_file(&path).unwrap();
+    }
+}
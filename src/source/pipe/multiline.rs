@@ -0,0 +1,92 @@
+use regex::Regex;
+
+/// Groups lines into records, a new record begins with a line matching the `start` pattern
+#[derive(Debug)]
+pub struct Grouper {
+    start: Option<Regex>,
+    lines: Vec<String>,
+}
+
+impl Grouper {
+    pub fn new(start: Option<Regex>) -> Self {
+        Grouper {
+            start,
+            lines: Vec::new(),
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+    /// Returns the previous group when `line` begins a new one
+    pub fn push(&mut self, line: String) -> Option<String> {
+        let start = match &self.start {
+            Some(start) => start,
+            None => return Some(line),
+        };
+
+        let group = if start.is_match(&line) {
+            self.flush()
+        } else {
+            None
+        };
+        self.lines.push(line);
+
+        group
+    }
+    /// Returns the pending group without its trailing empty lines
+    pub fn flush(&mut self) -> Option<String> {
+        while self.lines.last().is_some_and(|line| line.trim().is_empty()) {
+            self.lines.pop();
+        }
+        if self.lines.is_empty() {
+            return None;
+        }
+
+        let group = self.lines.join("\n");
+        self.lines.clear();
+
+        Some(group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::Grouper;
+
+    #[test]
+    fn main() {
+        let mut grouper = Grouper::new(Some(Regex::new(r"^\d{4}-").unwrap()));
+        let lines = vec![
+            "  orphan",
+            "2021-01-01 first",
+            "2021-01-01 second",
+            "Traceback:",
+            "  at main",
+            "",
+            "  ",
+        ];
+
+        let groups: Vec<String> = lines
+            .into_iter()
+            .filter_map(|line| grouper.push(line.to_owned()))
+            .collect();
+        assert_eq!(groups, vec!["  orphan", "2021-01-01 first"]);
+        assert!(!grouper.is_empty());
+        assert_eq!(
+            grouper.flush().as_deref(),
+            Some("2021-01-01 second\nTraceback:\n  at main")
+        );
+        assert!(grouper.is_empty());
+        assert_eq!(grouper.flush(), None);
+    }
+
+    #[test]
+    fn single_line() {
+        let mut grouper = Grouper::new(None);
+
+        assert_eq!(grouper.push(String::from("line")).as_deref(), Some("line"));
+        assert!(grouper.is_empty());
+    }
+}
@@ -0,0 +1,35 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Watch {
+    pub title: Option<String>,
+    pub name: Option<String>,
+    pub cmdline: Option<String>,
+    pub pidfile: Option<PathBuf>,
+    #[serde(default = "Watch::default_min")]
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Watch {
+    fn default_min() -> usize {
+        1
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct ProcessLogSourceConfig {
+    pub interval: u64,
+    pub watches: Vec<Watch>,
+}
+
+impl Default for ProcessLogSourceConfig {
+    fn default() -> Self {
+        ProcessLogSourceConfig {
+            interval: 10,
+            watches: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::time::Duration;
+use tokio::time::delay_for;
+
+use crate::source::{LogRecord, LogSource, LogSourceStream};
+
+mod config;
+mod procfs;
+mod watcher;
+pub use self::config::ProcessLogSourceConfig;
+use self::watcher::Watcher;
+
+pub struct ProcessLogSource {
+    interval: Duration,
+    watchers: Vec<Watcher>,
+}
+
+impl ProcessLogSource {
+    pub fn new(config: ProcessLogSourceConfig) -> Result<Self> {
+        let watchers = config
+            .watches
+            .into_iter()
+            .map(Watcher::new)
+            .collect::<Result<_>>()?;
+
+        Ok(ProcessLogSource {
+            interval: Duration::from_secs(config.interval),
+            watchers,
+        })
+    }
+    fn scan(&mut self) -> Result<Vec<LogRecord>> {
+        let processes = procfs::processes()?;
+        let uptime = procfs::system_uptime()?;
+
+        let records = self
+            .watchers
+            .iter_mut()
+            .flat_map(|watcher| watcher.check(&processes, uptime))
+            .collect();
+
+        Ok(records)
+    }
+}
+
+impl LogSource for ProcessLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+async fn run(mut source: ProcessLogSource, mut tx: Sender<Result<LogRecord>>) {
+    loop {
+        let results = match source.scan() {
+            Ok(records) => records.into_iter().map(Ok).collect(),
+            Err(error) => vec![Err(error)],
+        };
+
+        for result in results {
+            if tx.send(result).await.is_err() {
+                return;
+            }
+        }
+
+        delay_for(source.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{process::Command, time::Duration};
+    use tokio::time::delay_for;
+
+    use crate::source::{Level, LogSource};
+
+    use super::{config::Watch, ProcessLogSource, ProcessLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let mut child = Command::new("sleep").arg("1234.5").spawn().unwrap();
+
+        let config = ProcessLogSourceConfig {
+            interval: 1,
+            watches: vec![Watch {
+                title: Some(String::from("Sleep")),
+                name: Some(String::from("sleep")),
+                cmdline: Some(String::from(r"^sleep 1234\.5$")),
+                pidfile: None,
+                min: 1,
+                max: None,
+            }],
+        };
+
+        let source = ProcessLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        // the first scan happens immediately
+        delay_for(Duration::from_millis(200)).await;
+        child.kill().unwrap();
+        child.wait().unwrap();
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, "Sleep exited");
+        assert!(record
+            .body
+            .starts_with(&format!("PID {}, uptime", child.id())));
+
+        let record = stream.next().await.unwrap().unwrap();
+        assert_eq!(record.title, "Sleep: 0 running");
+        assert_eq!(record.level, Some(Level::Error));
+    }
+}
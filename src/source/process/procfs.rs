@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Result};
+use std::{fs, time::Duration};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Process {
+    pub pid: u32,
+    pub name: String,
+    pub cmdline: String,
+    /// Time since the system boot when the process was started
+    pub started: Duration,
+    /// Resident set size in bytes
+    pub rss: u64,
+}
+
+impl Process {
+    pub fn uptime(&self, system_uptime: Duration) -> Duration {
+        system_uptime.checked_sub(self.started).unwrap_or_default()
+    }
+}
+
+struct Stat {
+    name: String,
+    start_ticks: u64,
+    rss_pages: u64,
+}
+
+/// Parses /proc/[pid]/stat, the name is in parentheses and may contain spaces and parentheses itself
+fn parse_stat(stat: &str) -> Option<Stat> {
+    let name_start = stat.find('(')? + 1;
+    let name_end = stat.rfind(')')?;
+    let name = stat.get(name_start..name_end)?.to_owned();
+
+    // fields after the name, starting from the 3rd one (state)
+    let fields: Vec<&str> = stat.get(name_end + 1..)?.split_whitespace().collect();
+    let start_ticks = fields.get(19)?.parse().ok()?;
+    let rss_pages = fields.get(21)?.parse().ok()?;
+
+    Some(Stat {
+        name,
+        start_ticks,
+        rss_pages,
+    })
+}
+
+fn read_process(pid: u32, clock_ticks: u64, page_size: u64) -> Option<Process> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let stat = parse_stat(&stat)?;
+    let cmdline = fs::read(format!("/proc/{}/cmdline", pid)).ok()?;
+    let cmdline = String::from_utf8_lossy(&cmdline)
+        .trim_end_matches('\0')
+        .replace('\0', " ");
+
+    Some(Process {
+        pid,
+        name: stat.name,
+        cmdline,
+        started: Duration::from_millis(stat.start_ticks * 1000 / clock_ticks),
+        rss: stat.rss_pages * page_size,
+    })
+}
+
+/// Lists the running processes, the ones which exit while reading are skipped
+pub fn processes() -> Result<Vec<Process>> {
+    let clock_ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as u64;
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64;
+
+    let mut processes = Vec::new();
+    for entry in fs::read_dir("/proc")? {
+        let pid = match entry?
+            .file_name()
+            .to_str()
+            .and_then(|name| name.parse().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+
+        if let Some(process) = read_process(pid, clock_ticks, page_size) {
+            processes.push(process);
+        }
+    }
+
+    Ok(processes)
+}
+
+pub fn system_uptime() -> Result<Duration> {
+    let uptime = fs::read_to_string("/proc/uptime")?;
+    let seconds: f64 = uptime
+        .split_whitespace()
+        .next()
+        .and_then(|seconds| seconds.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid /proc/uptime: {}", uptime))?;
+
+    Ok(Duration::from_millis((seconds * 1000.0) as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process;
+
+    use super::{parse_stat, processes};
+
+    #[test]
+    fn stat() {
+        let stat = "4242 (my (weird) app) S 1 4242 4242 0 -1 4194560 1402 0 0 0 25 12 0 0 \
+                    20 0 4 0 394207 725110784 5120 18446744073709551615 1 1 0 0 0 0 0 0 0";
+        let stat = parse_stat(stat).unwrap();
+
+        assert_eq!(stat.name, "my (weird) app");
+        assert_eq!(stat.start_ticks, 394_207);
+        assert_eq!(stat.rss_pages, 5120);
+        assert!(parse_stat("4242 (truncated) S 1").is_none());
+    }
+
+    #[test]
+    fn current() {
+        let pid = process::id();
+        let current = processes()
+            .unwrap()
+            .into_iter()
+            .find(|process| process.pid == pid)
+            .unwrap();
+
+        assert!(current.rss > 0);
+        assert!(!current.cmdline.is_empty());
+    }
+}
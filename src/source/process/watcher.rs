@@ -0,0 +1,297 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::{collections::BTreeMap, fs, path::PathBuf, time::Duration};
+
+use super::{config::Watch, procfs::Process};
+use crate::source::{Level, LogRecord};
+
+/// Tracks the processes matching the watch between the scans
+#[derive(Debug)]
+pub struct Watcher {
+    title: String,
+    name: Option<String>,
+    cmdline: Option<Regex>,
+    pidfile: Option<PathBuf>,
+    min: usize,
+    max: Option<usize>,
+    known: BTreeMap<u32, Process>,
+    in_range: bool,
+}
+
+impl Watcher {
+    pub fn new(watch: Watch) -> Result<Self> {
+        let title = watch
+            .title
+            .clone()
+            .or_else(|| watch.name.clone())
+            .or_else(|| watch.cmdline.clone())
+            .or_else(|| {
+                watch
+                    .pidfile
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+            })
+            .ok_or_else(|| anyhow!("Process watch requires name, cmdline or pidfile"))?;
+        let cmdline = match &watch.cmdline {
+            Some(cmdline) => Some(Regex::new(cmdline)?),
+            None => None,
+        };
+
+        Ok(Watcher {
+            title,
+            name: watch.name,
+            cmdline,
+            pidfile: watch.pidfile,
+            min: watch.min,
+            max: watch.max,
+            known: BTreeMap::new(),
+            in_range: true,
+        })
+    }
+    fn matches(&self, process: &Process, pidfile_pid: Option<Option<u32>>) -> bool {
+        let name_matches = self.name.as_ref().is_none_or(|name| {
+            // the kernel truncates process names to 15 characters
+            process.name == *name || (process.name.len() == 15 && name.starts_with(&process.name))
+        });
+        let cmdline_matches = self
+            .cmdline
+            .as_ref()
+            .is_none_or(|cmdline| cmdline.is_match(&process.cmdline));
+        let pid_matches = pidfile_pid.is_none_or(|pid| pid == Some(process.pid));
+
+        name_matches && cmdline_matches && pid_matches
+    }
+    fn range(&self) -> String {
+        match self.max {
+            Some(max) if max == self.min => format!("exactly {}", max),
+            Some(max) => format!("from {} to {}", self.min, max),
+            None => format!("at least {}", self.min),
+        }
+    }
+    pub fn check(&mut self, processes: &[Process], system_uptime: Duration) -> Vec<LogRecord> {
+        let pidfile_pid = self.pidfile.as_ref().map(|path| {
+            let pid = fs::read_to_string(path).ok()?;
+            pid.trim().parse().ok()
+        });
+        let current: BTreeMap<u32, Process> = processes
+            .iter()
+            .filter(|process| self.matches(process, pidfile_pid))
+            .map(|process| (process.pid, process.clone()))
+            .collect();
+
+        let is_same = |process: &Process| {
+            current
+                .get(&process.pid)
+                .is_some_and(|current| current.started == process.started)
+        };
+        let mut started = current.values().filter(|process| {
+            self.known
+                .get(&process.pid)
+                .is_none_or(|known| known.started != process.started)
+        });
+
+        let mut records = Vec::new();
+        for process in self.known.values().filter(|process| !is_same(process)) {
+            let last_seen = describe(process, system_uptime);
+            let record = match started.next() {
+                Some(new) => LogRecord::new(
+                    format!("{} respawned", self.title),
+                    format!(
+                        "PID {} → {}, previous process: {}",
+                        process.pid, new.pid, last_seen
+                    ),
+                ),
+                None => LogRecord::new(
+                    format!("{} exited", self.title),
+                    format!("PID {}, {}", process.pid, last_seen),
+                ),
+            };
+
+            records.push(record.with_level(Level::Warning));
+        }
+
+        let count = current.len();
+        let in_range = count >= self.min && self.max.is_none_or(|max| count <= max);
+        if in_range != self.in_range {
+            self.in_range = in_range;
+
+            let title = format!("{}: {} running", self.title, count);
+            let record = if in_range {
+                LogRecord::new(title, format!("Back to the expected {}", self.range()))
+                    .with_level(Level::Info)
+            } else {
+                LogRecord::new(title, format!("Expected {}", self.range())).with_level(Level::Error)
+            };
+
+            records.push(record.with_thread(self.title.clone(), in_range));
+        }
+
+        self.known = current;
+        records
+    }
+}
+
+fn describe(process: &Process, system_uptime: Duration) -> String {
+    format!(
+        "uptime {}, RSS {:.1} MiB",
+        format_duration(process.uptime(system_uptime)),
+        process.rss as f64 / 1024.0 / 1024.0
+    )
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::{format_duration, Watcher};
+    use crate::source::{
+        process::{config::Watch, procfs::Process},
+        Level, LogRecord,
+    };
+
+    fn process(pid: u32, name: &str, started: u64) -> Process {
+        Process {
+            pid,
+            name: name.to_owned(),
+            cmdline: format!("/usr/bin/{} --daemon", name),
+            started: Duration::from_secs(started),
+            rss: 64 * 1024 * 1024,
+        }
+    }
+
+    fn watch() -> Watch {
+        Watch {
+            title: None,
+            name: None,
+            cmdline: None,
+            pidfile: None,
+            min: 1,
+            max: None,
+        }
+    }
+
+    #[test]
+    fn main() {
+        let mut watcher = Watcher::new(Watch {
+            name: Some(String::from("worker")),
+            max: Some(2),
+            ..watch()
+        })
+        .unwrap();
+        let uptime = Duration::from_secs(4000);
+
+        let processes = vec![process(10, "worker", 100), process(11, "other", 100)];
+        assert_eq!(watcher.check(&processes, uptime), vec![]);
+
+        // the PID changes between the scans
+        let processes = vec![process(12, "worker", 3990)];
+        let expected = vec![LogRecord::new(
+            "worker respawned",
+            "PID 10 → 12, previous process: uptime 1h 5m, RSS 64.0 MiB",
+        )
+        .with_level(Level::Warning)];
+        assert_eq!(watcher.check(&processes, uptime), expected);
+
+        let processes = vec![
+            process(12, "worker", 3990),
+            process(13, "worker", 3995),
+            process(14, "worker", 3995),
+        ];
+        let expected = vec![LogRecord::new("worker: 3 running", "Expected from 1 to 2")
+            .with_level(Level::Error)
+            .with_thread("worker", false)];
+        assert_eq!(watcher.check(&processes, uptime), expected);
+
+        let processes = vec![process(13, "worker", 3995)];
+        let expected = vec![
+            LogRecord::new("worker exited", "PID 12, uptime 10s, RSS 64.0 MiB")
+                .with_level(Level::Warning),
+            LogRecord::new("worker exited", "PID 14, uptime 5s, RSS 64.0 MiB")
+                .with_level(Level::Warning),
+            LogRecord::new("worker: 1 running", "Back to the expected from 1 to 2")
+                .with_level(Level::Info)
+                .with_thread("worker", true),
+        ];
+        assert_eq!(watcher.check(&processes, uptime), expected);
+
+        let expected = vec![
+            LogRecord::new("worker exited", "PID 13, uptime 5s, RSS 64.0 MiB")
+                .with_level(Level::Warning),
+            LogRecord::new("worker: 0 running", "Expected from 1 to 2")
+                .with_level(Level::Error)
+                .with_thread("worker", false),
+        ];
+        assert_eq!(watcher.check(&[], uptime), expected);
+    }
+
+    #[test]
+    fn matching() {
+        let pidfile = env::temp_dir().join("logram_test_process.pid");
+        fs::write(&pidfile, "21\n").unwrap();
+
+        let processes = vec![
+            process(20, "java", 0),
+            process(21, "java", 0),
+            process(22, "very-long-proce", 0),
+        ];
+        let count = |watch: Watch| {
+            let mut watcher = Watcher::new(Watch { min: 0, ..watch }).unwrap();
+            watcher.check(&processes, Duration::from_secs(0));
+            watcher.known.len()
+        };
+
+        assert_eq!(
+            count(Watch {
+                name: Some(String::from("java")),
+                ..watch()
+            }),
+            2
+        );
+        assert_eq!(
+            count(Watch {
+                pidfile: Some(pidfile.clone()),
+                ..watch()
+            }),
+            1
+        );
+        assert_eq!(
+            count(Watch {
+                name: Some(String::from("very-long-process-name")),
+                ..watch()
+            }),
+            1
+        );
+        assert_eq!(
+            count(Watch {
+                cmdline: Some(String::from(r"^/usr/bin/java\b")),
+                pidfile: Some(env::temp_dir().join("logram_test_missing.pid")),
+                ..watch()
+            }),
+            0
+        );
+        assert!(Watcher::new(watch()).is_err());
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(125)), "2m 5s");
+        assert_eq!(format_duration(Duration::from_secs(7500)), "2h 5m");
+        assert_eq!(format_duration(Duration::from_secs(180_000)), "2d 2h 0m");
+    }
+}
@@ -1,20 +1,140 @@
-#[derive(Debug, PartialEq)]
+use anyhow::{anyhow, Error};
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+/// Severity of a log record, the same as syslog severity levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Emergency,
+    Alert,
+    Critical,
+    Error,
+    Warning,
+    Notice,
+    Info,
+    Debug,
+}
+
+impl Level {
+    #[cfg(any(feature = "ls_syslog", feature = "ls_gelf", feature = "ls_kmsg"))]
+    pub fn from_syslog(severity: u8) -> Option<Self> {
+        let level = match severity {
+            0 => Level::Emergency,
+            1 => Level::Alert,
+            2 => Level::Critical,
+            3 => Level::Error,
+            4 => Level::Warning,
+            5 => Level::Notice,
+            6 => Level::Info,
+            7 => Level::Debug,
+            _ => return None,
+        };
+
+        Some(level)
+    }
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Level::Emergency => "emergency",
+            Level::Alert => "alert",
+            Level::Critical => "critical",
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Notice => "notice",
+            Level::Info => "info",
+            Level::Debug => "debug",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let lowercase = name.to_lowercase();
+        // numbered levels, like DEBUG1-5 of PostgreSQL and trace1-8 of Apache
+        let unnumbered = lowercase.trim_end_matches(|c: char| c.is_ascii_digit());
+
+        let level = match lowercase.as_str() {
+            "emergency" | "emerg" | "panic" => Level::Emergency,
+            "alert" => Level::Alert,
+            "critical" | "crit" | "fatal" => Level::Critical,
+            "error" | "err" => Level::Error,
+            "warning" | "warn" => Level::Warning,
+            "notice" => Level::Notice,
+            "info" | "information" | "log" => Level::Info,
+            "debug" | "trace" => Level::Debug,
+            _ if unnumbered == "debug" || unnumbered == "trace" => Level::Debug,
+            _ => return Err(anyhow!("Unknown level: {}", name)),
+        };
+
+        Ok(level)
+    }
+}
+
+impl<'de> Deserialize<'de> for Level {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        name.parse().map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Groups related records, e.g. notifications about an alert and its resolving
+#[derive(Debug, Clone, PartialEq)]
+pub struct Thread {
+    pub key: String,
+    /// The record is the last one in the thread
+    pub close: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct LogRecord {
     pub title: String,
     pub body: String,
+    pub level: Option<Level>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub metadata: BTreeMap<String, String>,
+    pub thread: Option<Thread>,
 }
 
+// the builders are shared by all sources, so a build with a few of them doesn't use some
+#[allow(dead_code)]
 impl LogRecord {
     pub fn new<Ts: Into<String>, Bs: Into<String>>(title: Ts, body: Bs) -> Self {
         LogRecord {
             title: title.into(),
             body: body.into(),
+            level: None,
+            timestamp: None,
+            metadata: BTreeMap::new(),
+            thread: None,
         }
     }
     pub fn only_title(title: String) -> Self {
-        LogRecord {
-            title,
-            body: String::new(),
-        }
+        LogRecord::new(title, String::new())
+    }
+    pub fn with_level(mut self, level: Level) -> Self {
+        self.level = Some(level);
+        self
+    }
+    pub fn with_timestamp(mut self, timestamp: DateTime<Utc>) -> Self {
+        self.timestamp = Some(timestamp);
+        self
+    }
+    pub fn with_metadata<Ks: Into<String>, Vs: Into<String>>(mut self, key: Ks, value: Vs) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+    pub fn with_thread<Ks: Into<String>>(mut self, key: Ks, close: bool) -> Self {
+        let key = key.into();
+        self.thread = Some(Thread { key, close });
+        self
     }
 }
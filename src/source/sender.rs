@@ -0,0 +1,159 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Receiver, SendError, Sender},
+    executor,
+    stream::Stream,
+    task::{Context, Poll},
+    SinkExt,
+};
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use super::{config::OverflowPolicy, LogRecord};
+
+/// Sending half of the record channel for log sources that run in a blocking thread
+pub struct BlockingSender {
+    tx: Sender<Result<LogRecord>>,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl BlockingSender {
+    pub fn channel(buffer: usize, overflow: OverflowPolicy) -> (BlockingSender, BlockingReceiver) {
+        let (tx, rx) = futures_mpsc::channel(buffer);
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let sender = BlockingSender {
+            tx,
+            overflow,
+            dropped: dropped.clone(),
+        };
+
+        (sender, BlockingReceiver { rx, dropped })
+    }
+    /// Sends the record, fails only if the receiving half is gone
+    pub fn send(&mut self, record: Result<LogRecord>) -> Result<(), SendError> {
+        match self.overflow {
+            OverflowPolicy::Block => executor::block_on(self.tx.send(record)),
+            OverflowPolicy::Drop => {
+                self.send_dropped_notice()?;
+                self.try_send(record)
+            }
+        }
+    }
+    fn send_dropped_notice(&mut self) -> Result<(), SendError> {
+        let dropped = self.dropped.swap(0, Ordering::SeqCst);
+        if dropped == 0 {
+            return Ok(());
+        }
+
+        match self.tx.try_send(Ok(dropped_notice(dropped))) {
+            Ok(()) => Ok(()),
+            Err(error) if error.is_full() => {
+                self.dropped.fetch_add(dropped, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(error) => Err(error.into_send_error()),
+        }
+    }
+    fn try_send(&mut self, record: Result<LogRecord>) -> Result<(), SendError> {
+        match self.tx.try_send(record) {
+            Ok(()) => Ok(()),
+            Err(error) if error.is_full() => {
+                self.dropped.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            }
+            Err(error) => Err(error.into_send_error()),
+        }
+    }
+}
+
+/// Receiving half of the record channel, reports the dropped records itself once
+/// the channel is drained, so a burst followed by silence isn't left unreported
+pub struct BlockingReceiver {
+    rx: Receiver<Result<LogRecord>>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl Stream for BlockingReceiver {
+    type Item = Result<LogRecord>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = Pin::new(&mut self.rx).poll_next(cx);
+        if let Poll::Ready(Some(_)) = poll {
+            return poll;
+        }
+
+        match self.dropped.swap(0, Ordering::SeqCst) {
+            0 => poll,
+            dropped => Poll::Ready(Some(Ok(dropped_notice(dropped)))),
+        }
+    }
+}
+
+fn dropped_notice(dropped: usize) -> LogRecord {
+    LogRecord::only_title(format!("{} records dropped", dropped))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+
+    use crate::source::{config::OverflowPolicy, LogRecord};
+
+    use super::BlockingSender;
+
+    #[tokio::test]
+    async fn drop_policy() {
+        let (mut sender, mut rx) = BlockingSender::channel(1, OverflowPolicy::Drop);
+
+        for index in 0..5 {
+            let record = LogRecord::new("title", format!("record {}", index));
+            sender.send(Ok(record)).unwrap();
+        }
+
+        let first: Vec<LogRecord> = (&mut rx).take(2).map(Result::unwrap).collect().await;
+        sender
+            .send(Ok(LogRecord::new("title", "record 5")))
+            .unwrap();
+        drop(sender);
+        let second: Vec<LogRecord> = rx.map(Result::unwrap).collect().await;
+
+        let expected_first = vec![
+            LogRecord::new("title", "record 0"),
+            LogRecord::new("title", "record 1"),
+        ];
+        let expected_second = vec![
+            LogRecord::only_title(String::from("3 records dropped")),
+            LogRecord::new("title", "record 5"),
+        ];
+
+        assert_eq!(first, expected_first);
+        assert_eq!(second, expected_second);
+    }
+
+    #[tokio::test]
+    async fn drop_policy_without_next_record() {
+        let (mut sender, rx) = BlockingSender::channel(1, OverflowPolicy::Drop);
+
+        for index in 0..5 {
+            let record = LogRecord::new("title", format!("record {}", index));
+            sender.send(Ok(record)).unwrap();
+        }
+
+        // the sender stays alive, but sends nothing after the burst
+        let actual: Vec<LogRecord> = rx.take(3).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("title", "record 0"),
+            LogRecord::new("title", "record 1"),
+            LogRecord::only_title(String::from("3 records dropped")),
+        ];
+
+        assert_eq!(actual, expected);
+        drop(sender);
+    }
+}
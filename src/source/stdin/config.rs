@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+use crate::source::{Level, ParserConfig};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct StdinLogSourceConfig {
+    pub title: String,
+    pub level: Option<Level>,
+    #[serde(flatten)]
+    pub parser: ParserConfig,
+}
+
+impl Default for StdinLogSourceConfig {
+    fn default() -> Self {
+        StdinLogSourceConfig {
+            title: String::from("stdin"),
+            level: None,
+            parser: ParserConfig::default(),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use futures::{channel::mpsc as futures_mpsc, executor, SinkExt};
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    thread,
+};
+
+use crate::source::{Level, LogRecord, LogSource, LogSourceStream, Parser};
+
+mod config;
+pub use self::config::StdinLogSourceConfig;
+
+struct Target {
+    title: String,
+    level: Option<Level>,
+    parser: Parser,
+}
+
+impl Target {
+    fn to_record(&self, line: String) -> LogRecord {
+        let mut record = LogRecord::new(self.title.clone(), line);
+        if let Some(level) = self.level {
+            record = record.with_level(level);
+        }
+
+        self.parser.parse_record(record)
+    }
+}
+
+/// Sends every line of stdin, the stream ends with the input
+pub struct StdinLogSource {
+    reader: Box<dyn Read + Send>,
+    target: Target,
+}
+
+impl StdinLogSource {
+    pub fn new(config: StdinLogSourceConfig) -> Result<Self> {
+        StdinLogSource::with_reader(config, Box::new(io::stdin()))
+    }
+    fn with_reader(config: StdinLogSourceConfig, reader: Box<dyn Read + Send>) -> Result<Self> {
+        let target = Target {
+            title: config.title,
+            level: config.level,
+            parser: Parser::new(config.parser)?,
+        };
+
+        Ok(StdinLogSource { reader, target })
+    }
+}
+
+impl LogSource for StdinLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (mut tx, rx) = futures_mpsc::channel(10);
+
+        thread::spawn(move || {
+            let (reader, target) = (self.reader, self.target);
+            let mut reader = BufReader::new(reader);
+            loop {
+                let mut buffer = Vec::new();
+                let record = match reader.read_until(b'\n', &mut buffer) {
+                    Ok(0) => break,
+                    Ok(_) => {
+                        let line = String::from_utf8_lossy(&buffer)
+                            .trim_end_matches(&['\r', '\n'][..])
+                            .to_owned();
+                        if line.is_empty() {
+                            continue;
+                        }
+
+                        Ok(target.to_record(line))
+                    }
+                    Err(error) => {
+                        // the error is sent once, as reading again would likely fail the same way
+                        let _ = executor::block_on(tx.send(Err(error.into())));
+                        break;
+                    }
+                };
+
+                if executor::block_on(tx.send(record)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Box::pin(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::io::{self, Cursor, Read};
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{StdinLogSource, StdinLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let config = StdinLogSourceConfig {
+            title: String::from("backup"),
+            level: Some(Level::Notice),
+            ..Default::default()
+        };
+        let input = Cursor::new("dumping database\r\n\ncompressing\nuploading");
+
+        let source = StdinLogSource::with_reader(config, Box::new(input)).unwrap();
+        let actual: Vec<LogRecord> = source.into_stream().map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("backup", "dumping database").with_level(Level::Notice),
+            LogRecord::new("backup", "compressing").with_level(Level::Notice),
+            LogRecord::new("backup", "uploading").with_level(Level::Notice),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    struct Broken;
+
+    impl Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("broken pipe"))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_error() {
+        let source = StdinLogSource::with_reader(Default::default(), Box::new(Broken)).unwrap();
+        let results: Vec<_> = source.into_stream().collect().await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].as_ref().unwrap_err().to_string(), "broken pipe");
+    }
+}
@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use std::{net::SocketAddr, path::PathBuf};
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SyslogLogSourceConfig {
+    pub udp: Vec<SocketAddr>,
+    pub tcp: Vec<SocketAddr>,
+    pub unix: Option<PathBuf>,
+}
+
+impl Default for SyslogLogSourceConfig {
+    fn default() -> Self {
+        SyslogLogSourceConfig {
+            udp: vec![SocketAddr::from(([0, 0, 0, 0], 514))],
+            tcp: Vec::new(),
+            unix: None,
+        }
+    }
+}
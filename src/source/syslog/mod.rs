@@ -0,0 +1,344 @@
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::{
+    fs, net,
+    os::unix::{fs::FileTypeExt, net as unix_net},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+    net::{TcpListener, TcpStream, UdpSocket, UnixDatagram},
+};
+
+use crate::source::{Level, LogRecord, LogSource, LogSourceStream};
+
+mod config;
+mod parser;
+pub use self::config::SyslogLogSourceConfig;
+use self::parser::SyslogMessage;
+
+type RecordSender = Sender<Result<LogRecord>>;
+
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+/// Digits of the octet counting length of the largest message, with the space
+const MAX_LENGTH_DIGITS: usize = 6;
+
+pub struct SyslogLogSource {
+    udp: Vec<UdpSocket>,
+    tcp: Vec<TcpListener>,
+    unix: Option<UnixDatagram>,
+}
+
+impl SyslogLogSource {
+    pub fn new(config: SyslogLogSourceConfig) -> Result<Self> {
+        let mut udp = Vec::new();
+        for addr in config.udp {
+            let socket = net::UdpSocket::bind(addr)?;
+            udp.push(UdpSocket::from_std(socket)?);
+        }
+
+        let mut tcp = Vec::new();
+        for addr in config.tcp {
+            let listener = net::TcpListener::bind(addr)?;
+            tcp.push(TcpListener::from_std(listener)?);
+        }
+
+        let unix = match config.unix {
+            Some(path) => {
+                if path.exists() {
+                    if !fs::metadata(&path)?.file_type().is_socket() {
+                        return Err(anyhow!("{} exists and is not a socket", path.display()));
+                    }
+
+                    fs::remove_file(&path)?;
+                }
+
+                let socket = unix_net::UnixDatagram::bind(path)?;
+                Some(UnixDatagram::from_std(socket)?)
+            }
+            None => None,
+        };
+
+        Ok(SyslogLogSource { udp, tcp, unix })
+    }
+}
+
+impl LogSource for SyslogLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        for socket in self.udp {
+            tokio::spawn(listen_udp(socket, tx.clone()));
+        }
+        for listener in self.tcp {
+            tokio::spawn(listen_tcp(listener, tx.clone()));
+        }
+        if let Some(socket) = self.unix {
+            tokio::spawn(listen_unix(socket, tx));
+        }
+
+        Box::pin(rx)
+    }
+}
+
+fn message_to_record(message: SyslogMessage, peer: Option<String>) -> LogRecord {
+    let hostname = message.hostname.clone().or(peer);
+    let title = match (&message.app_name, &hostname) {
+        (Some(app_name), Some(hostname)) => format!("{}@{}", app_name, hostname),
+        (Some(name), None) | (None, Some(name)) => name.clone(),
+        (None, None) => String::from("syslog"),
+    };
+
+    let mut record = LogRecord::new(title, message.message.clone())
+        .with_metadata("facility", message.facility_name())
+        .with_metadata("severity", message.severity.to_string());
+
+    if let Some(level) = Level::from_syslog(message.severity) {
+        record = record.with_level(level);
+    }
+    if let Some(timestamp) = message.timestamp {
+        record = record.with_timestamp(timestamp);
+    }
+
+    let fields = vec![
+        ("hostname", hostname),
+        ("app_name", message.app_name),
+        ("proc_id", message.proc_id),
+        ("msg_id", message.msg_id),
+        ("structured_data", message.structured_data),
+    ];
+    for (key, value) in fields {
+        if let Some(value) = value {
+            record = record.with_metadata(key, value);
+        }
+    }
+
+    record
+}
+
+fn bytes_to_record(bytes: &[u8], peer: Option<String>) -> LogRecord {
+    let message = parser::parse(&String::from_utf8_lossy(bytes));
+
+    message_to_record(message, peer)
+}
+
+async fn listen_udp(mut socket: UdpSocket, mut tx: RecordSender) {
+    let mut buffer = vec![0; MAX_MESSAGE_SIZE];
+
+    loop {
+        let result = match socket.recv_from(&mut buffer).await {
+            Ok((len, peer)) => Ok(bytes_to_record(&buffer[..len], Some(peer.ip().to_string()))),
+            Err(error) => Err(error.into()),
+        };
+
+        if tx.send(result).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn listen_unix(mut socket: UnixDatagram, mut tx: RecordSender) {
+    let mut buffer = vec![0; MAX_MESSAGE_SIZE];
+
+    loop {
+        let result = match socket.recv(&mut buffer).await {
+            Ok(len) => Ok(bytes_to_record(&buffer[..len], None)),
+            Err(error) => Err(error.into()),
+        };
+
+        if tx.send(result).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn listen_tcp(mut listener: TcpListener, mut tx: RecordSender) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let mut tx = tx.clone();
+
+                tokio::spawn(async move {
+                    let peer = peer.ip().to_string();
+                    if let Err(error) = read_tcp(stream, peer.clone(), tx.clone()).await {
+                        let error = error.context(format!("Syslog connection from {}", peer));
+                        let _ = tx.send(Err(error)).await;
+                    }
+                });
+            }
+            Err(error) => {
+                if tx.send(Err(error.into())).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Reads the messages framed either by octet counting or by newlines (RFC 6587)
+async fn read_tcp(stream: TcpStream, peer: String, mut tx: RecordSender) -> Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    loop {
+        let mut first = [0; 1];
+        if reader.read(&mut first).await? == 0 {
+            return Ok(());
+        }
+
+        let mut frame = Vec::new();
+        if first[0].is_ascii_digit() {
+            let mut len = first.to_vec();
+            let mut limited = (&mut reader).take(MAX_LENGTH_DIGITS as u64);
+            limited.read_until(b' ', &mut len).await?;
+
+            let digits = len.strip_suffix(b" ").unwrap_or(&len);
+            if len.last() != Some(&b' ') || !digits.iter().all(u8::is_ascii_digit) {
+                return Err(anyhow!(
+                    "Invalid syslog message length: {}",
+                    String::from_utf8_lossy(&len)
+                ));
+            }
+
+            let len: usize = String::from_utf8_lossy(digits).parse()?;
+            if len > MAX_MESSAGE_SIZE {
+                return Err(anyhow!("Syslog message is too large: {} bytes", len));
+            }
+
+            frame.resize(len, 0);
+            reader.read_exact(&mut frame).await?;
+        } else {
+            frame.push(first[0]);
+            let mut limited = (&mut reader).take(MAX_MESSAGE_SIZE as u64);
+            limited.read_until(b'\n', &mut frame).await?;
+            if frame.last() != Some(&b'\n') && frame.len() > MAX_MESSAGE_SIZE {
+                return Err(anyhow!(
+                    "Syslog message is larger than {} bytes",
+                    MAX_MESSAGE_SIZE
+                ));
+            }
+        }
+
+        let record = bytes_to_record(&frame, Some(peer.clone()));
+        if tx.send(Ok(record)).await.is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{
+        env, fs,
+        io::Write,
+        net::{TcpStream, UdpSocket},
+    };
+
+    use crate::source::{Level, LogRecord, LogSource};
+
+    use super::{SyslogLogSource, SyslogLogSourceConfig};
+
+    #[tokio::test]
+    async fn main() {
+        let config = SyslogLogSourceConfig {
+            udp: vec!["127.0.0.1:15140".parse().unwrap()],
+            tcp: vec!["127.0.0.1:15140".parse().unwrap()],
+            unix: None,
+        };
+
+        let source = SyslogLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+
+        let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+        udp.send_to(b"<11>1 - host app - - - udp message", "127.0.0.1:15140")
+            .unwrap();
+
+        let actual: Vec<LogRecord> = stream.take(1).map(Result::unwrap).collect().await;
+        let expected = vec![LogRecord::new("app@host", "udp message")
+            .with_level(Level::Error)
+            .with_metadata("facility", "user")
+            .with_metadata("severity", "3")
+            .with_metadata("hostname", "host")
+            .with_metadata("app_name", "app")];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn tcp() {
+        let config = SyslogLogSourceConfig {
+            udp: Vec::new(),
+            tcp: vec!["127.0.0.1:15141".parse().unwrap()],
+            unix: None,
+        };
+
+        let source = SyslogLogSource::new(config).unwrap();
+        let mut stream = source.into_stream();
+
+        let mut tcp = TcpStream::connect("127.0.0.1:15141").unwrap();
+        tcp.write_all(b"<14>newline framed\n25 <12>octet counted message")
+            .unwrap();
+
+        let actual: Vec<LogRecord> = (&mut stream).take(2).map(Result::unwrap).collect().await;
+        let expected = vec![
+            LogRecord::new("127.0.0.1", "newline framed")
+                .with_level(Level::Info)
+                .with_metadata("facility", "user")
+                .with_metadata("severity", "6")
+                .with_metadata("hostname", "127.0.0.1"),
+            LogRecord::new("127.0.0.1", "octet counted message")
+                .with_level(Level::Warning)
+                .with_metadata("facility", "user")
+                .with_metadata("severity", "4")
+                .with_metadata("hostname", "127.0.0.1"),
+        ];
+        assert_eq!(actual, expected);
+
+        tcp.write_all(b"100000 <12>huge").unwrap();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Syslog connection from 127.0.0.1: Syslog message is too large: 100000 bytes"
+        );
+
+        let mut tcp = TcpStream::connect("127.0.0.1:15141").unwrap();
+        tcp.write_all(b"12x <12>message").unwrap();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Syslog connection from 127.0.0.1: Invalid syslog message length: 12x "
+        );
+
+        let mut tcp = TcpStream::connect("127.0.0.1:15141").unwrap();
+        tcp.write_all(b"11111111111111111111 <12>message").unwrap();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Syslog connection from 127.0.0.1: Invalid syslog message length: 1111111"
+        );
+
+        let mut tcp = TcpStream::connect("127.0.0.1:15141").unwrap();
+        tcp.write_all(&[b'a'; 70_000]).unwrap();
+        let error = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Syslog connection from 127.0.0.1: Syslog message is larger than 65536 bytes"
+        );
+    }
+
+    #[test]
+    fn unix_path() {
+        let path = env::temp_dir().join("logram_test_syslog_not_socket");
+        fs::write(&path, "data").unwrap();
+
+        let config = SyslogLogSourceConfig {
+            udp: Vec::new(),
+            tcp: Vec::new(),
+            unix: Some(path.clone()),
+        };
+        assert!(SyslogLogSource::new(config).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "data");
+    }
+}
@@ -0,0 +1,274 @@
+use chrono::{DateTime, Utc};
+
+use crate::source::timestamp::bsd_timestamp_at;
+
+static FACILITIES: [&str; 24] = [
+    "kern",
+    "user",
+    "mail",
+    "daemon",
+    "auth",
+    "syslog",
+    "lpr",
+    "news",
+    "uucp",
+    "cron",
+    "authpriv",
+    "ftp",
+    "ntp",
+    "security",
+    "console",
+    "solaris-cron",
+    "local0",
+    "local1",
+    "local2",
+    "local3",
+    "local4",
+    "local5",
+    "local6",
+    "local7",
+];
+
+#[derive(Debug, PartialEq)]
+pub struct SyslogMessage {
+    pub facility: u8,
+    pub severity: u8,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub hostname: Option<String>,
+    pub app_name: Option<String>,
+    pub proc_id: Option<String>,
+    pub msg_id: Option<String>,
+    pub structured_data: Option<String>,
+    pub message: String,
+}
+
+impl SyslogMessage {
+    pub fn facility_name(&self) -> &'static str {
+        FACILITIES
+            .get(self.facility as usize)
+            .cloned()
+            .unwrap_or("unknown")
+    }
+}
+
+/// Parses RFC 5424 or RFC 3164 message, a message without a valid priority
+/// is considered as user.notice message without a header
+pub fn parse(input: &str) -> SyslogMessage {
+    let input = input.trim_end_matches(['\n', '\r', '\0']);
+
+    let (priority, rest) = match parse_priority(input) {
+        Some(parsed) => parsed,
+        None => return raw_message(13, input),
+    };
+
+    match rest.strip_prefix("1 ") {
+        Some(rest) => parse_rfc5424(priority, rest),
+        None => parse_rfc3164(priority, rest),
+    }
+}
+
+fn raw_message(priority: u8, message: &str) -> SyslogMessage {
+    SyslogMessage {
+        facility: priority >> 3,
+        severity: priority & 7,
+        timestamp: None,
+        hostname: None,
+        app_name: None,
+        proc_id: None,
+        msg_id: None,
+        structured_data: None,
+        message: String::from(message),
+    }
+}
+
+fn parse_priority(input: &str) -> Option<(u8, &str)> {
+    let rest = input.strip_prefix('<')?;
+    let end = rest.find('>')?;
+    let digits = &rest[..end];
+
+    if digits.is_empty() || digits.len() > 3 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    match digits.parse::<u8>() {
+        Ok(priority) if priority < 192 => Some((priority, &rest[end + 1..])),
+        _ => None,
+    }
+}
+
+fn nil(field: &str) -> Option<String> {
+    match field {
+        "-" | "" => None,
+        field => Some(String::from(field)),
+    }
+}
+
+fn parse_rfc5424(priority: u8, input: &str) -> SyslogMessage {
+    let mut fields = input.splitn(6, ' ');
+    let mut next = || fields.next().unwrap_or("");
+
+    let timestamp = DateTime::parse_from_rfc3339(next())
+        .ok()
+        .map(|time| time.with_timezone(&Utc));
+    let hostname = nil(next());
+    let app_name = nil(next());
+    let proc_id = nil(next());
+    let msg_id = nil(next());
+    let (structured_data, message) = split_structured_data(next());
+
+    SyslogMessage {
+        timestamp,
+        hostname,
+        app_name,
+        proc_id,
+        msg_id,
+        structured_data: nil(structured_data),
+        message: String::from(message.trim_start_matches('\u{feff}')),
+        ..raw_message(priority, "")
+    }
+}
+
+/// Splits the structured data elements (or nil value) from the message
+fn split_structured_data(input: &str) -> (&str, &str) {
+    if !input.starts_with('[') {
+        let (nil, message) = input.split_at(input.find(' ').unwrap_or(input.len()));
+        return (nil, message.strip_prefix(' ').unwrap_or(message));
+    }
+
+    let (mut in_element, mut in_value, mut escaped) = (false, false, false);
+    for (index, c) in input.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_value => escaped = true,
+            '"' if in_element => in_value = !in_value,
+            '[' if !in_value => in_element = true,
+            ']' if !in_value => in_element = false,
+            ' ' if !in_element => {
+                return (&input[..index], &input[index + 1..]);
+            }
+            _ => {}
+        }
+    }
+
+    (input, "")
+}
+
+fn parse_rfc3164(priority: u8, input: &str) -> SyslogMessage {
+    let (timestamp, rest) = match parse_bsd_timestamp(input) {
+        Some(parsed) => parsed,
+        None => return raw_message(priority, input.trim_start()),
+    };
+
+    let (hostname, rest) = rest.split_at(rest.find(' ').unwrap_or(rest.len()));
+    let rest = rest.trim_start();
+
+    let tag_end = rest.find([':', '[', ' ']).unwrap_or(rest.len());
+    let (app_name, mut rest) = rest.split_at(tag_end);
+
+    let mut proc_id = None;
+    if let Some(pid_rest) = rest.strip_prefix('[') {
+        if let Some(end) = pid_rest.find(']') {
+            proc_id = nil(&pid_rest[..end]);
+            rest = &pid_rest[end + 1..];
+        }
+    }
+
+    let message = rest.strip_prefix(':').unwrap_or(rest).trim_start();
+
+    SyslogMessage {
+        timestamp: Some(timestamp),
+        hostname: nil(hostname),
+        app_name: nil(app_name),
+        proc_id,
+        ..raw_message(priority, message)
+    }
+}
+
+/// Parses `Mmm dd hh:mm:ss` timestamp, the year is assumed to be the current one
+fn parse_bsd_timestamp(input: &str) -> Option<(DateTime<Utc>, &str)> {
+    let timestamp = input.get(..15)?;
+    let rest = input[15..].strip_prefix(' ')?;
+
+    Some((bsd_timestamp_at(timestamp, Utc::now())?, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Datelike, Duration, TimeZone, Utc};
+
+    use super::{parse, SyslogMessage};
+
+    #[test]
+    fn rfc5424() {
+        let input = "<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 \
+                     [exampleSDID@32473 iut=\"3\" eventSource=\"Application\"] \u{feff}An application event";
+        let expected = SyslogMessage {
+            facility: 20,
+            severity: 5,
+            timestamp: Some(Utc.ymd(2003, 10, 11).and_hms_milli(22, 14, 15, 3)),
+            hostname: Some(String::from("mymachine.example.com")),
+            app_name: Some(String::from("evntslog")),
+            proc_id: None,
+            msg_id: Some(String::from("ID47")),
+            structured_data: Some(String::from(
+                "[exampleSDID@32473 iut=\"3\" eventSource=\"Application\"]",
+            )),
+            message: String::from("An application event"),
+        };
+
+        let actual = parse(input);
+        assert_eq!(actual, expected);
+        assert_eq!(actual.facility_name(), "local4");
+
+        let actual = parse("<34>1 - host su 1234 - - 'su root' failed\n");
+        assert_eq!(actual.severity, 2);
+        assert_eq!(actual.timestamp, None);
+        assert_eq!(actual.proc_id.as_deref(), Some("1234"));
+        assert_eq!(actual.structured_data, None);
+        assert_eq!(actual.message, "'su root' failed");
+    }
+
+    #[test]
+    fn rfc3164() {
+        let actual = parse("<34>Oct  1 22:14:15 mymachine su[230]: 'su root' failed for lonvick");
+        let now = Utc::now();
+        let this_year = Utc.ymd(now.year(), 10, 1).and_hms(22, 14, 15);
+        let year = if this_year > now + Duration::days(1) {
+            now.year() - 1
+        } else {
+            now.year()
+        };
+
+        assert_eq!(actual.facility_name(), "auth");
+        assert_eq!(actual.severity, 2);
+        assert_eq!(
+            actual.timestamp,
+            Some(Utc.ymd(year, 10, 1).and_hms(22, 14, 15))
+        );
+        assert_eq!(actual.hostname.as_deref(), Some("mymachine"));
+        assert_eq!(actual.app_name.as_deref(), Some("su"));
+        assert_eq!(actual.proc_id.as_deref(), Some("230"));
+        assert_eq!(actual.message, "'su root' failed for lonvick");
+
+        let actual = parse("<13>Feb 15 08:00:01 router kernel: link down");
+        assert_eq!(actual.app_name.as_deref(), Some("kernel"));
+        assert_eq!(actual.proc_id, None);
+        assert_eq!(actual.message, "link down");
+    }
+
+    #[test]
+    fn invalid() {
+        let actual = parse("just a message");
+        assert_eq!(actual.facility_name(), "user");
+        assert_eq!(actual.severity, 5);
+        assert_eq!(actual.message, "just a message");
+
+        let actual = parse("<999>overflow");
+        assert_eq!(actual.message, "<999>overflow");
+
+        let actual = parse("<30>no timestamp here");
+        assert_eq!(actual.facility_name(), "daemon");
+        assert_eq!(actual.hostname, None);
+        assert_eq!(actual.message, "no timestamp here");
+    }
+}
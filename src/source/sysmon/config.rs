@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct Disk {
+    pub path: PathBuf,
+    pub usage: Option<f64>,
+    pub inodes: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct SysmonLogSourceConfig {
+    pub interval: u64,
+    pub hysteresis: f64,
+    pub disks: Vec<Disk>,
+    pub memory: Option<f64>,
+    pub swap: Option<f64>,
+    pub load: Option<f64>,
+}
+
+impl Default for SysmonLogSourceConfig {
+    fn default() -> Self {
+        SysmonLogSourceConfig {
+            interval: 60,
+            hysteresis: 5.0,
+            disks: Vec::new(),
+            memory: None,
+            swap: None,
+            load: None,
+        }
+    }
+}
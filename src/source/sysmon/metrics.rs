@@ -0,0 +1,108 @@
+use anyhow::{anyhow, Result};
+use std::{collections::HashMap, ffi::CString, io, mem, os::unix::ffi::OsStrExt, path::Path};
+
+#[derive(Debug, PartialEq)]
+pub struct DiskUsage {
+    pub blocks: f64,
+    pub inodes: f64,
+}
+
+/// Percentages of used blocks (as reported by df) and inodes of the filesystem
+pub fn disk_usage(path: &Path) -> Result<DiskUsage> {
+    let c_path = CString::new(path.as_os_str().as_bytes())?;
+    let mut stat: libc::statvfs = unsafe { mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        let error = io::Error::last_os_error();
+        return Err(anyhow!("{}: {}", path.display(), error));
+    }
+
+    let used = (stat.f_blocks - stat.f_bfree) as f64;
+    let available = stat.f_bavail as f64;
+    let inodes_used = (stat.f_files - stat.f_ffree) as f64;
+
+    Ok(DiskUsage {
+        blocks: percent(used, used + available),
+        inodes: percent(inodes_used, stat.f_files as f64),
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MemoryUsage {
+    pub memory: f64,
+    pub swap: f64,
+}
+
+/// Parses the content of /proc/meminfo
+pub fn memory_usage(meminfo: &str) -> Result<MemoryUsage> {
+    let values: HashMap<&str, f64> = meminfo
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let value = value.trim().trim_end_matches(" kB").parse().ok()?;
+            Some((key, value))
+        })
+        .collect();
+    let get = |key| {
+        values
+            .get(key)
+            .copied()
+            .ok_or_else(|| anyhow!("{} is missing in /proc/meminfo", key))
+    };
+
+    let total = get("MemTotal")?;
+    let swap_total = get("SwapTotal")?;
+
+    Ok(MemoryUsage {
+        memory: percent(total - get("MemAvailable")?, total),
+        swap: percent(swap_total - get("SwapFree")?, swap_total),
+    })
+}
+
+/// Parses the 5 minutes load average from the content of /proc/loadavg
+pub fn load_average(loadavg: &str) -> Result<f64> {
+    loadavg
+        .split_whitespace()
+        .nth(1)
+        .and_then(|load| load.parse().ok())
+        .ok_or_else(|| anyhow!("Invalid /proc/loadavg: {}", loadavg))
+}
+
+fn percent(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        part / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{disk_usage, load_average, memory_usage, MemoryUsage};
+
+    #[test]
+    fn main() {
+        let meminfo = "MemTotal:        8000000 kB\n\
+                       MemFree:          500000 kB\n\
+                       MemAvailable:    2000000 kB\n\
+                       SwapTotal:       1000000 kB\n\
+                       SwapFree:         750000 kB\n";
+        let expected = MemoryUsage {
+            memory: 75.0,
+            swap: 25.0,
+        };
+        assert_eq!(memory_usage(meminfo).unwrap(), expected);
+
+        let meminfo = "MemTotal: 1000 kB\nMemAvailable: 1000 kB\nSwapTotal: 0 kB\nSwapFree: 0 kB\n";
+        assert_eq!(memory_usage(meminfo).unwrap().swap, 0.0);
+        assert!(memory_usage("MemTotal: 1000 kB\n").is_err());
+
+        assert_eq!(load_average("0.52 1.25 0.98 2/431 12345\n").unwrap(), 1.25);
+        assert!(load_average("").is_err());
+
+        let usage = disk_usage(Path::new("/")).unwrap();
+        assert!(usage.blocks >= 0.0 && usage.blocks <= 100.0);
+        assert!(disk_usage(Path::new("/nonexistent")).is_err());
+    }
+}
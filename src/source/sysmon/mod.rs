@@ -0,0 +1,259 @@
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{self as futures_mpsc, Sender},
+    SinkExt,
+};
+use std::{fs, path::PathBuf, time::Duration};
+use tokio::{task, time::delay_for};
+
+use crate::source::{Level, LogRecord, LogSource, LogSourceStream};
+
+mod config;
+mod metrics;
+mod threshold;
+pub use self::config::SysmonLogSourceConfig;
+use self::threshold::{Threshold, Transition};
+
+#[derive(Debug)]
+enum Metric {
+    DiskUsage(PathBuf),
+    InodeUsage(PathBuf),
+    Memory,
+    Swap,
+    Load,
+}
+
+impl Metric {
+    fn name(&self) -> String {
+        match self {
+            Metric::DiskUsage(path) => format!("Disk usage on {}", path.display()),
+            Metric::InodeUsage(path) => format!("Inode usage on {}", path.display()),
+            Metric::Memory => String::from("Memory usage"),
+            Metric::Swap => String::from("Swap usage"),
+            Metric::Load => String::from("Load average"),
+        }
+    }
+    fn read(&self) -> Result<f64> {
+        let value = match self {
+            Metric::DiskUsage(path) => metrics::disk_usage(path)?.blocks,
+            Metric::InodeUsage(path) => metrics::disk_usage(path)?.inodes,
+            Metric::Memory => metrics::memory_usage(&fs::read_to_string("/proc/meminfo")?)?.memory,
+            Metric::Swap => metrics::memory_usage(&fs::read_to_string("/proc/meminfo")?)?.swap,
+            Metric::Load => metrics::load_average(&fs::read_to_string("/proc/loadavg")?)?,
+        };
+
+        Ok(value)
+    }
+    fn format(&self, value: f64) -> String {
+        match self {
+            Metric::Load => format!("{:.2}", value),
+            _ => format!("{:.1}%", value),
+        }
+    }
+}
+
+struct Check {
+    metric: Metric,
+    threshold: Threshold,
+    failing: bool,
+}
+
+impl Check {
+    fn new(metric: Metric, threshold: Threshold) -> Self {
+        Check {
+            metric,
+            threshold,
+            failing: false,
+        }
+    }
+    /// Reads the metric, a persistent read error is reported only once
+    fn run(&mut self) -> Option<Result<LogRecord>> {
+        match self.metric.read() {
+            Ok(value) => {
+                self.failing = false;
+                self.check(value).map(Ok)
+            }
+            Err(_) if self.failing => None,
+            Err(error) => {
+                self.failing = true;
+                Some(Err(error))
+            }
+        }
+    }
+    fn check(&mut self, value: f64) -> Option<LogRecord> {
+        let transition = self.threshold.check(value)?;
+
+        let name = self.metric.name();
+        let value = self.metric.format(value);
+        let limit = self.metric.format(self.threshold.limit);
+        let record = match transition {
+            Transition::Exceeded => LogRecord::new(
+                format!("{} is high", name),
+                format!("{} is {}, threshold is {}", name, value, limit),
+            )
+            .with_level(Level::Warning)
+            .with_thread(name.clone(), false),
+            Transition::Recovered => LogRecord::new(
+                format!("{} is back to normal", name),
+                format!("{} is {}", name, value),
+            )
+            .with_level(Level::Info)
+            .with_thread(name.clone(), true),
+        };
+
+        Some(record)
+    }
+}
+
+pub struct SysmonLogSource {
+    interval: Duration,
+    checks: Vec<Check>,
+}
+
+impl SysmonLogSource {
+    pub fn new(config: SysmonLogSourceConfig) -> Result<Self> {
+        let mut metrics = Vec::new();
+        for disk in config.disks {
+            // fail early on invalid paths
+            metrics::disk_usage(&disk.path)?;
+
+            if let Some(usage) = disk.usage {
+                metrics.push((Metric::DiskUsage(disk.path.clone()), usage));
+            }
+            if let Some(inodes) = disk.inodes {
+                metrics.push((Metric::InodeUsage(disk.path), inodes));
+            }
+        }
+        if let Some(memory) = config.memory {
+            metrics.push((Metric::Memory, memory));
+        }
+        if let Some(swap) = config.swap {
+            metrics.push((Metric::Swap, swap));
+        }
+        if let Some(load) = config.load {
+            metrics.push((Metric::Load, load));
+        }
+
+        let hysteresis = config.hysteresis;
+        let checks = metrics
+            .into_iter()
+            .map(|(metric, limit)| Check::new(metric, Threshold::new(limit, hysteresis)))
+            .collect();
+
+        Ok(SysmonLogSource {
+            interval: Duration::from_secs(config.interval),
+            checks,
+        })
+    }
+}
+
+impl LogSource for SysmonLogSource {
+    fn into_stream(self) -> LogSourceStream {
+        let (tx, rx) = futures_mpsc::channel(10);
+
+        tokio::spawn(run(self, tx));
+
+        Box::pin(rx)
+    }
+}
+
+async fn run(source: SysmonLogSource, mut tx: Sender<Result<LogRecord>>) {
+    let mut checks = source.checks;
+
+    loop {
+        // statvfs and /proc reads block
+        let task = task::spawn_blocking(move || {
+            let results: Vec<_> = checks.iter_mut().filter_map(Check::run).collect();
+            (checks, results)
+        });
+        let results = match task.await {
+            Ok((returned, results)) => {
+                checks = returned;
+                results
+            }
+            Err(error) => {
+                let _ = tx.send(Err(error.into())).await;
+                return;
+            }
+        };
+
+        for result in results {
+            if tx.send(result).await.is_err() {
+                return;
+            }
+        }
+
+        delay_for(source.interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use std::{env, fs, path::PathBuf};
+
+    use crate::source::{Level, LogSource};
+
+    use super::{
+        config::Disk, threshold::Threshold, Check, Metric, SysmonLogSource, SysmonLogSourceConfig,
+    };
+
+    #[tokio::test]
+    async fn main() {
+        let config = SysmonLogSourceConfig {
+            disks: vec![Disk {
+                path: PathBuf::from("/"),
+                usage: Some(0.0),
+                inodes: None,
+            }],
+            memory: Some(0.0),
+            load: Some(1_000_000.0),
+            ..Default::default()
+        };
+
+        let source = SysmonLogSource::new(config).unwrap();
+        let stream = source.into_stream();
+
+        let actual: Vec<_> = stream.take(2).map(Result::unwrap).collect().await;
+
+        let titles: Vec<&str> = actual.iter().map(|record| record.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec!["Disk usage on / is high", "Memory usage is high"]
+        );
+
+        let record = &actual[1];
+        assert_eq!(record.level, Some(Level::Warning));
+        assert!(record.body.ends_with("threshold is 0.0%"));
+        assert_eq!(record.thread.as_ref().unwrap().key, "Memory usage");
+        assert!(!record.thread.as_ref().unwrap().close);
+
+        let config = SysmonLogSourceConfig {
+            disks: vec![Disk {
+                path: PathBuf::from("/nonexistent"),
+                usage: Some(90.0),
+                inodes: None,
+            }],
+            ..Default::default()
+        };
+        assert!(SysmonLogSource::new(config).is_err());
+    }
+
+    #[test]
+    fn read_error() {
+        let path = env::temp_dir().join("logram_test_sysmon_disk");
+        fs::create_dir_all(&path).unwrap();
+        let mut check = Check::new(Metric::DiskUsage(path.clone()), Threshold::new(0.0, 0.0));
+
+        assert!(check.run().unwrap().is_ok());
+        fs::remove_dir(&path).unwrap();
+        assert!(check.run().unwrap().is_err());
+        assert!(check.run().is_none());
+
+        // the error is reported again after the metric was read
+        fs::create_dir(&path).unwrap();
+        assert!(check.run().is_none());
+        fs::remove_dir(&path).unwrap();
+        assert!(check.run().unwrap().is_err());
+    }
+}
@@ -0,0 +1,56 @@
+#[derive(Debug, PartialEq)]
+pub enum Transition {
+    Exceeded,
+    Recovered,
+}
+
+/// Threshold with hysteresis: once exceeded, the value has to drop
+/// below the lower `recover` limit to be considered recovered
+#[derive(Debug)]
+pub struct Threshold {
+    pub limit: f64,
+    recover: f64,
+    exceeded: bool,
+}
+
+impl Threshold {
+    /// `hysteresis` is a percentage of the limit
+    pub fn new(limit: f64, hysteresis: f64) -> Self {
+        Threshold {
+            limit,
+            recover: limit * (1.0 - hysteresis / 100.0),
+            exceeded: false,
+        }
+    }
+    pub fn check(&mut self, value: f64) -> Option<Transition> {
+        if !self.exceeded && value >= self.limit {
+            self.exceeded = true;
+            Some(Transition::Exceeded)
+        } else if self.exceeded && value < self.recover {
+            self.exceeded = false;
+            Some(Transition::Recovered)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Threshold, Transition};
+
+    #[test]
+    fn main() {
+        let mut threshold = Threshold::new(90.0, 10.0);
+
+        assert_eq!(threshold.check(50.0), None);
+        assert_eq!(threshold.check(90.0), Some(Transition::Exceeded));
+        assert_eq!(threshold.check(95.0), None);
+        // hovering between the recover limit (81) and the limit
+        assert_eq!(threshold.check(85.0), None);
+        assert_eq!(threshold.check(91.0), None);
+        assert_eq!(threshold.check(80.0), Some(Transition::Recovered));
+        assert_eq!(threshold.check(85.0), None);
+        assert_eq!(threshold.check(92.0), Some(Transition::Exceeded));
+    }
+}
@@ -0,0 +1,167 @@
+use anyhow::Result;
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Seek, SeekFrom},
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+};
+
+/// Follows the file like `tail -F`: when the file is rotated or truncated,
+/// the rest of the old file is read and the new one is read from the start
+pub struct FileTail {
+    path: PathBuf,
+    reader: BufReader<File>,
+    inode: u64,
+    position: u64,
+    partial: Vec<u8>,
+}
+
+impl FileTail {
+    /// Opens the file to read either the lines appended from now on, or all of them
+    pub fn new(path: PathBuf, from_end: bool) -> Result<Self> {
+        let mut file = File::open(&path)?;
+        let inode = file.metadata()?.ino();
+        let position = if from_end {
+            file.seek(SeekFrom::End(0))?
+        } else {
+            0
+        };
+
+        Ok(FileTail {
+            path,
+            reader: BufReader::new(file),
+            inode,
+            position,
+            partial: Vec::new(),
+        })
+    }
+    /// Returns complete lines appended since the last call
+    pub fn read_lines(&mut self) -> Result<Vec<String>> {
+        let mut lines = self.read_available()?;
+
+        let metadata = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata,
+            // rotated, the new file isn't created yet
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(lines),
+            Err(error) => return Err(error.into()),
+        };
+        if metadata.ino() != self.inode || metadata.len() < self.position {
+            *self = FileTail::new(self.path.clone(), false)?;
+            lines.extend(self.read_available()?);
+        }
+
+        Ok(lines)
+    }
+    /// Returns complete lines appended since the last call, sleeping for `timeout`
+    /// when there are none, for sources which poll the file in a loop
+    #[cfg(any(feature = "ls_auth", feature = "ls_audit"))]
+    pub fn wait_lines(&mut self, timeout: std::time::Duration) -> Result<Vec<String>> {
+        let lines = self.read_lines()?;
+        if lines.is_empty() {
+            std::thread::sleep(timeout);
+        }
+
+        Ok(lines)
+    }
+    fn read_available(&mut self) -> Result<Vec<String>> {
+        let mut lines = Vec::new();
+
+        loop {
+            let len = self.reader.read_until(b'\n', &mut self.partial)?;
+            if len == 0 {
+                break;
+            }
+            self.position += len as u64;
+
+            // the line is still being written
+            if self.partial.last() != Some(&b'\n') {
+                break;
+            }
+
+            let line = String::from_utf8_lossy(&self.partial);
+            lines.push(line.trim_end_matches(&['\r', '\n'][..]).to_owned());
+            self.partial.clear();
+        }
+
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env,
+        fs::{self, OpenOptions},
+        io::Write,
+    };
+
+    use super::FileTail;
+
+    #[test]
+    fn rotation() {
+        let path = env::temp_dir().join("logram_test_tail.log");
+        fs::write(&path, "old line\n").unwrap();
+
+        let mut tail = FileTail::new(path.clone(), true).unwrap();
+        assert!(tail.read_lines().unwrap().is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"first\nsec").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["first"]);
+        file.write_all(b"ond\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["second"]);
+
+        // rotation: the file is moved away and a new one is created
+        let rotated = env::temp_dir().join("logram_test_tail.log.1");
+        fs::rename(&path, &rotated).unwrap();
+        file.write_all(b"last in rotated\n").unwrap();
+        fs::write(&path, "new file\n").unwrap();
+        assert_eq!(
+            tail.read_lines().unwrap(),
+            vec!["last in rotated", "new file"]
+        );
+
+        // truncation
+        fs::write(&path, "").unwrap();
+        assert!(tail.read_lines().unwrap().is_empty());
+        fs::write(&path, "after truncate\n").unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["after truncate"]);
+    }
+
+    #[test]
+    fn from_start() {
+        let path = env::temp_dir().join("logram_test_tail_start.log");
+        fs::write(&path, "old line\r\n").unwrap();
+
+        let mut tail = FileTail::new(path.clone(), false).unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["old line"]);
+
+        // a character split between writes
+        let text = "ключ\n".as_bytes();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&text[..3]).unwrap();
+        assert!(tail.read_lines().unwrap().is_empty());
+        file.write_all(&text[3..]).unwrap();
+        assert_eq!(tail.read_lines().unwrap(), vec!["ключ"]);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(any(feature = "ls_auth", feature = "ls_audit"))]
+    fn wait_lines() {
+        let path = env::temp_dir().join("logram_test_tail_wait.log");
+        fs::write(&path, "").unwrap();
+
+        let mut tail = FileTail::new(path.clone(), true).unwrap();
+        let timeout = std::time::Duration::from_millis(50);
+        let started = std::time::Instant::now();
+        assert!(tail.wait_lines(timeout).unwrap().is_empty());
+        assert!(started.elapsed() >= timeout);
+
+        fs::write(&path, "line\n").unwrap();
+        assert_eq!(tail.wait_lines(timeout).unwrap(), vec!["line"]);
+
+        fs::remove_file(&path).unwrap();
+    }
+}
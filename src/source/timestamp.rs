@@ -0,0 +1,50 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+
+/// Parses the syslog timestamp without year, like `Jan  2 03:04:05`. Dates more
+/// than a day ahead of `now` are from the last year, e.g. a December message
+/// received just after New Year
+pub fn bsd_timestamp_at(timestamp: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let timestamp = timestamp.replace("  ", " ");
+    let parse = |year: i32| {
+        let with_year = format!("{} {}", year, timestamp);
+        NaiveDateTime::parse_from_str(&with_year, "%Y %b %d %H:%M:%S")
+            .ok()
+            .map(|time| DateTime::<Utc>::from_utc(time, Utc))
+    };
+
+    match parse(now.year()) {
+        Some(time) if time - now <= Duration::days(1) => Some(time),
+        // Feb 29 of the last leap year doesn't parse with the current one
+        _ => parse(now.year() - 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use super::bsd_timestamp_at;
+
+    #[test]
+    fn bsd_year() {
+        let new_year = Utc.ymd(2021, 1, 1).and_hms(0, 0, 30);
+
+        assert_eq!(
+            bsd_timestamp_at("Dec 31 23:59:58", new_year),
+            Some(Utc.ymd(2020, 12, 31).and_hms(23, 59, 58))
+        );
+        assert_eq!(
+            bsd_timestamp_at("Jan  1 00:00:29", new_year),
+            Some(Utc.ymd(2021, 1, 1).and_hms(0, 0, 29))
+        );
+        // slightly ahead clocks of the senders
+        assert_eq!(
+            bsd_timestamp_at("Jan  1 12:00:00", new_year),
+            Some(Utc.ymd(2021, 1, 1).and_hms(12, 0, 0))
+        );
+        assert_eq!(
+            bsd_timestamp_at("Feb 29 10:00:00", Utc.ymd(2021, 3, 1).and_hms(0, 0, 0)),
+            Some(Utc.ymd(2020, 2, 29).and_hms(10, 0, 0))
+        );
+    }
+}
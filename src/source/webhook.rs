@@ -0,0 +1,200 @@
+use anyhow::Result;
+use futures::{channel::mpsc as futures_mpsc, Future, SinkExt, StreamExt};
+use hyper::{
+    body::Bytes,
+    header::{AUTHORIZATION, CONTENT_LENGTH},
+    server::{conn::AddrIncoming, Builder},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, StatusCode,
+};
+use std::{convert::Infallible, fmt::Display, sync::Arc};
+
+use super::{LogRecord, LogSourceStream};
+
+pub type Rejection = (StatusCode, String);
+
+/// Records of the request and the response which is sent once they are queued
+pub struct Accepted {
+    pub records: Vec<LogRecord>,
+    pub response: Response<Body>,
+}
+
+impl From<Vec<LogRecord>> for Accepted {
+    fn from(records: Vec<LogRecord>) -> Self {
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::ACCEPTED;
+
+        Accepted { records, response }
+    }
+}
+
+/// Runs an HTTP server which turns requests into log records with the handler
+pub fn serve<H, F, A>(builder: Builder<AddrIncoming>, handler: H) -> LogSourceStream
+where
+    H: Fn(Request<Body>) -> F + Send + Sync + 'static,
+    F: Future<Output = Result<A, Rejection>> + Send + 'static,
+    A: Into<Accepted>,
+{
+    let (tx, rx) = futures_mpsc::channel(10);
+
+    let handler = Arc::new(handler);
+    let mut error_tx = tx.clone();
+    let make_service = make_service_fn(move |_| {
+        let handler = handler.clone();
+        let tx = tx.clone();
+
+        async move {
+            let service = service_fn(move |req| {
+                let accepted = handler(req);
+                let mut tx = tx.clone();
+
+                async move {
+                    let result = match accepted.await.map(Into::into) {
+                        Ok(Accepted { records, response }) => {
+                            send_all(&mut tx, records).await.map(|_| response)
+                        }
+                        Err(rejection) => Err(rejection),
+                    };
+
+                    let response = result.unwrap_or_else(|(status, text)| {
+                        let mut response = Response::new(Body::from(text));
+                        *response.status_mut() = status;
+                        response
+                    });
+
+                    Ok::<_, Infallible>(response)
+                }
+            });
+
+            Ok::<_, Infallible>(service)
+        }
+    });
+
+    let server = builder.serve(make_service);
+    tokio::spawn(async move {
+        if let Err(error) = server.await {
+            let _ = error_tx.send(Err(error.into())).await;
+        }
+    });
+
+    Box::pin(rx)
+}
+
+async fn send_all(
+    tx: &mut futures_mpsc::Sender<Result<LogRecord>>,
+    records: Vec<LogRecord>,
+) -> Result<(), Rejection> {
+    for record in records {
+        if let Err(error) = tx.send(Ok(record)).await {
+            return Err((StatusCode::SERVICE_UNAVAILABLE, error.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that the request is POST and has the bearer token, if it's required
+pub fn check_request(req: &Request<Body>, token: Option<&str>) -> Result<(), Rejection> {
+    if req.method() != Method::POST {
+        let text = String::from("Only POST requests are allowed");
+        return Err((StatusCode::METHOD_NOT_ALLOWED, text));
+    }
+
+    if let Some(token) = token {
+        let expected = format!("Bearer {}", token);
+        let authorization = req.headers().get(AUTHORIZATION);
+
+        let valid = authorization
+            .is_some_and(|value| constant_time_eq(value.as_bytes(), expected.as_bytes()));
+        if !valid {
+            return Err((StatusCode::UNAUTHORIZED, String::from("Invalid token")));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compares the bytes in time which doesn't depend on the position of first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Reads the request body, rejecting it when it's larger than `max_body` bytes
+pub async fn read_body(req: Request<Body>, max_body: usize) -> Result<Bytes, Rejection> {
+    let length = req
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if length.is_some_and(|length| length > max_body) {
+        return Err(too_large(max_body));
+    }
+
+    let mut body = req.into_body();
+    let mut bytes = Vec::with_capacity(length.unwrap_or(0));
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(bad_request)?;
+        if bytes.len() + chunk.len() > max_body {
+            return Err(too_large(max_body));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Bytes::from(bytes))
+}
+
+pub fn too_large(max_body: usize) -> Rejection {
+    let text = format!("Request body is larger than {} bytes", max_body);
+    (StatusCode::PAYLOAD_TOO_LARGE, text)
+}
+
+pub fn bad_request<E: Display>(error: E) -> Rejection {
+    (StatusCode::BAD_REQUEST, error.to_string())
+}
+
+pub fn not_found() -> Rejection {
+    (StatusCode::NOT_FOUND, String::from("Unknown endpoint"))
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::{Body, Request, StatusCode};
+
+    use super::{check_request, read_body};
+
+    #[tokio::test]
+    async fn main() {
+        let request = |body: &'static str| Request::post("/").body(Body::from(body)).unwrap();
+
+        let bytes = read_body(request("12345678"), 8).await.unwrap();
+        assert_eq!(bytes.as_ref(), b"12345678");
+
+        // Body::from sets no Content-Length, so the limit is checked while reading
+        let rejection = read_body(request("123456789"), 8).await.unwrap_err();
+        assert_eq!(rejection.0, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = Request::post("/")
+            .header("Content-Length", "100")
+            .body(Body::empty())
+            .unwrap();
+        let rejection = read_body(request, 8).await.unwrap_err();
+        assert_eq!(rejection.0, StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = |token: &str| {
+            Request::post("/")
+                .header("Authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert!(check_request(&request("secret"), Some("secret")).is_ok());
+        for token in &["secreT", "secret2", "s", ""] {
+            let rejection = check_request(&request(token), Some("secret")).unwrap_err();
+            assert_eq!(rejection.0, StatusCode::UNAUTHORIZED);
+        }
+    }
+}
@@ -1,19 +1,27 @@
 use anyhow::{Error, Result};
 use reqwest::{Client, Proxy};
-use std::time::Duration;
-use teloxide::{prelude::Request, requests::ResponseResult, types::ParseMode, Bot, BotBuilder};
+use std::{path::Path, sync::Mutex, time::Duration};
+use teloxide::{
+    prelude::Request,
+    requests::{RequestWithFile, ResponseResult},
+    types::{InputFile, ParseMode},
+    Bot, BotBuilder,
+};
 
 mod config;
+mod threads;
 mod utils;
 
 use crate::source::LogRecord;
 pub use config::TelegramConfig;
+use threads::Threads;
 
 static USER_AGENT: &str = concat!("logram/", env!("CARGO_PKG_VERSION"));
 
 pub struct Telegram {
     bot: Bot,
     chat_id: String,
+    threads: Mutex<Threads>,
 }
 
 impl Telegram {
@@ -37,6 +45,7 @@ impl Telegram {
         Ok(Telegram {
             bot,
             chat_id: config.chat_id,
+            threads: Mutex::new(Threads::new()),
         })
     }
     pub async fn echo_id(&self) -> Result<()> {
@@ -67,11 +76,39 @@ impl Telegram {
         self.send(text).await
     }
     pub async fn send_record(&self, record: LogRecord) -> Result<()> {
-        let title = utils::escape(record.title);
-        let body = utils::escape(record.body);
-        let text = format!("*{}*```\n{}```", title, body);
+        let text = format_record(&record);
 
-        self.send(text).await
+        let thread = match record.thread {
+            Some(thread) => thread,
+            None => return self.send(text).await,
+        };
+
+        // The closed thread is forgotten even if the reply fails to be sent
+        if thread.close {
+            let reply_to = self.threads.lock().unwrap().close(&thread.key);
+            self.send_reply(text, reply_to).await?;
+
+            return Ok(());
+        }
+
+        let reply_to = self.threads.lock().unwrap().get(&thread.key);
+        let message_id = self.send_reply(text, reply_to).await?;
+        self.threads.lock().unwrap().open(thread.key, message_id);
+
+        Ok(())
+    }
+    /// Sends the file with the record as its caption
+    pub async fn send_file(&self, record: LogRecord, path: &Path) -> Result<()> {
+        let chat_id = self.chat_id.clone();
+        let caption = format_record(&record);
+
+        self.bot
+            .send_document(chat_id, InputFile::file(path))
+            .caption(caption)
+            .send()
+            .await??;
+
+        Ok(())
     }
     pub async fn send_error(&self, error: Error) -> Result<()> {
         let text = format!("*Error:* {}", utils::escape(error));
@@ -79,9 +116,67 @@ impl Telegram {
         self.send(text).await
     }
     async fn send(&self, text: String) -> Result<()> {
-        let chat_id = self.chat_id.clone();
-        self.bot.send_message(chat_id, text).send().await?;
+        self.send_reply(text, None).await?;
 
         Ok(())
     }
+    async fn send_reply(&self, text: String, reply_to: Option<i32>) -> Result<i32> {
+        let chat_id = self.chat_id.clone();
+        let mut request = self.bot.send_message(chat_id, text);
+        if let Some(message_id) = reply_to {
+            request = request.reply_to_message_id(message_id);
+        }
+
+        let message = request.send().await?;
+
+        Ok(message.id)
+    }
+}
+
+fn format_record(record: &LogRecord) -> String {
+    let title = match record.level {
+        Some(level) => utils::escape(format!("{}: {}", level, record.title)),
+        None => utils::escape(&record.title),
+    };
+    let body = utils::escape(&record.body);
+    let mut text = format!("*{}*```\n{}```", title, body);
+
+    if let Some(timestamp) = record.timestamp {
+        let timestamp = timestamp.format("%Y-%m-%d %H:%M:%S%.3f UTC").to_string();
+        text.push_str(&format!("\n_time_: {}", utils::escape(timestamp)));
+    }
+
+    for (key, value) in &record.metadata {
+        text.push_str(&format!(
+            "\n_{}_: {}",
+            utils::escape(key),
+            utils::escape(value)
+        ));
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use crate::source::{Level, LogRecord};
+
+    use super::format_record;
+
+    #[test]
+    fn main() {
+        let record = LogRecord::new("app container", "panic: index out of range [3]")
+            .with_level(Level::Error)
+            .with_timestamp(Utc.ymd(2021, 1, 2).and_hms_milli(3, 4, 5, 120))
+            .with_metadata("stream", "stderr")
+            .with_metadata("container_id", "4f2a1c");
+
+        let expected = "*error: app container*```\npanic: index out of range \\[3\\]```\n\
+                        _time_: 2021\\-01\\-02 03:04:05\\.120 UTC\n\
+                        _container\\_id_: 4f2a1c\n\
+                        _stream_: stderr";
+        assert_eq!(format_record(&record), expected);
+    }
 }
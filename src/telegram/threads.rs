@@ -0,0 +1,76 @@
+use std::collections::{HashMap, VecDeque};
+
+/// Maximum number of open threads, the oldest ones are forgotten above it
+const MAX_THREADS: usize = 1000;
+
+/// Message IDs of the first messages of open threads, which later records reply to
+pub struct Threads {
+    messages: HashMap<String, i32>,
+    order: VecDeque<String>,
+    capacity: usize,
+}
+
+impl Threads {
+    pub fn new() -> Self {
+        Threads::with_capacity(MAX_THREADS)
+    }
+    fn with_capacity(capacity: usize) -> Self {
+        Threads {
+            messages: HashMap::new(),
+            order: VecDeque::new(),
+            capacity,
+        }
+    }
+    pub fn get(&self, key: &str) -> Option<i32> {
+        self.messages.get(key).cloned()
+    }
+    /// Remembers the first message of the thread, evicting the oldest thread when full
+    pub fn open(&mut self, key: String, message_id: i32) {
+        if self.messages.contains_key(&key) {
+            return;
+        }
+
+        while self.order.len() >= self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => self.messages.remove(&oldest),
+                None => break,
+            };
+        }
+
+        self.order.push_back(key.clone());
+        self.messages.insert(key, message_id);
+    }
+    pub fn close(&mut self, key: &str) -> Option<i32> {
+        let message_id = self.messages.remove(key)?;
+        self.order.retain(|other| other != key);
+
+        Some(message_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Threads;
+
+    #[test]
+    fn main() {
+        let mut threads = Threads::with_capacity(2);
+
+        threads.open(String::from("a"), 1);
+        threads.open(String::from("a"), 2);
+        assert_eq!(threads.get("a"), Some(1));
+
+        threads.open(String::from("b"), 3);
+        threads.open(String::from("c"), 4);
+        assert_eq!(threads.get("a"), None);
+        assert_eq!(threads.get("b"), Some(3));
+        assert_eq!(threads.get("c"), Some(4));
+
+        assert_eq!(threads.close("b"), Some(3));
+        assert_eq!(threads.close("b"), None);
+
+        threads.open(String::from("d"), 5);
+        assert_eq!(threads.get("c"), Some(4));
+        assert_eq!(threads.get("d"), Some(5));
+    }
+}
@@ -31,16 +31,15 @@ pub fn extract_message(message: &Message) -> (&'static str, i64, String) {
     }
 }
 
+/// Escapes the characters reserved by MarkdownV2
 pub fn escape<S: Display>(text: S) -> String {
-    text.to_string()
-        .replace(".", "\\.")
-        .replace("_", "\\_")
-        .replace("*", "\\*")
-        .replace("<", "\\<")
-        .replace(">", "\\>")
-        .replace("-", "\\-")
-        .replace("{", "\\{")
-        .replace("}", "\\}")
-        .replace("(", "\\(")
-        .replace(")", "\\)")
+    let mut escaped = String::new();
+    for c in text.to_string().chars() {
+        if "\\_*[]()~`>#+-=|{}.!".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
 }